@@ -55,39 +55,36 @@ fn copy_ascii_files(target_dir: &str) {
 
     match fs::read_dir(source_dir) {
         Ok(entries) => {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_file() && path.extension().map_or(false, |ext| ext == "txt") {
-                        if let Some(file_name) = path.file_name() {
-                            let target_file = Path::new(target_dir).join(file_name);
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+                    if let Some(file_name) = path.file_name() {
+                        let target_file = Path::new(target_dir).join(file_name);
 
-                            // Only copy if the file doesn't exist or is older than the source
-                            let should_copy = if target_file.exists() {
-                                if let (Ok(source_meta), Ok(target_meta)) =
-                                    (fs::metadata(&path), fs::metadata(&target_file))
+                        // Only copy if the file doesn't exist or is older than the source
+                        let should_copy = if target_file.exists() {
+                            if let (Ok(source_meta), Ok(target_meta)) =
+                                (fs::metadata(&path), fs::metadata(&target_file))
+                            {
+                                if let (Ok(source_time), Ok(target_time)) =
+                                    (source_meta.modified(), target_meta.modified())
                                 {
-                                    if let (Ok(source_time), Ok(target_time)) =
-                                        (source_meta.modified(), target_meta.modified())
-                                    {
-                                        source_time > target_time
-                                    } else {
-                                        true // If we can't get timestamps, copy anyway
-                                    }
+                                    source_time > target_time
                                 } else {
-                                    true
+                                    true // If we can't get timestamps, copy anyway
                                 }
                             } else {
                                 true
-                            };
+                            }
+                        } else {
+                            true
+                        };
 
-                            if should_copy {
-                                match fs::copy(&path, &target_file) {
-                                    Ok(_) => println!("Copied ASCII file: {:?}", file_name),
-                                    Err(e) => eprintln!(
-                                        "Failed to copy ASCII file {:?}: {}",
-                                        file_name, e
-                                    ),
+                        if should_copy {
+                            match fs::copy(&path, &target_file) {
+                                Ok(_) => println!("Copied ASCII file: {:?}", file_name),
+                                Err(e) => {
+                                    eprintln!("Failed to copy ASCII file {:?}: {}", file_name, e)
                                 }
                             }
                         }
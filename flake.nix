@@ -21,7 +21,7 @@
           
           swiftfetch = pkgs.rustPlatform.buildRustPackage rec {
             pname = "swiftfetch";
-            version = "0.1.3";
+            version = "0.2.0";
             src = ./.;
 
             cargoLock.lockFile = ./Cargo.lock;
@@ -1,62 +1,179 @@
-//! Hardware information collection (CPU, GPU, Memory, Disk)
+//! Hardware information collection (CPU, GPU, Memory, Swap, Disk)
 
-use crate::data::{CpuInfo, DiskInfo, GpuInfo, HardwareInfo, MemoryInfo};
+use super::registry::{Collector, SystemInfo};
+use crate::data::{CpuInfo, DiskInfo, Gpu, GpuInfo, GpuKind, MemoryInfo, Value};
 use crate::error::Result;
 use crate::utils::{command::*, parsing::*};
 use std::fs;
 
-/// Collect all hardware information (parallelized for speed)
-pub fn collect_hardware_info() -> Result<HardwareInfo> {
-    // Collect CPU, GPU, memory, and disk info in parallel
-    let ((cpu, gpu), (memory, disk)) = rayon::join(
-        || rayon::join(|| collect_cpu_info(), || collect_gpu_info()),
-        || rayon::join(|| collect_memory_info(), || collect_disk_info()),
-    );
-
-    Ok(HardwareInfo {
-        cpu: cpu?,
-        gpu: gpu?,
-        memory: memory?,
-        disk: disk?,
-    })
-}
-
 /// Collect CPU information
 pub fn collect_cpu_info() -> Result<CpuInfo> {
     Ok(CpuInfo {
         brand: read_cpu_info()?,
+        temperature: crate::collectors::sensors::get_cpu_temperature(),
     })
 }
 
 /// Collect GPU information
 pub fn collect_gpu_info() -> Result<GpuInfo> {
-    let all_gpus = detect_all_gpus().unwrap_or_else(|_| vec!["Unknown GPU".to_string()]);
-    let primary = if all_gpus.is_empty() {
-        "Unknown GPU".to_string()
-    } else {
-        all_gpus[0].clone()
-    };
-
-    Ok(GpuInfo { primary, all_gpus })
+    Ok(GpuInfo {
+        all_gpus: detect_all_gpus().unwrap_or_default(),
+    })
 }
 
 /// Collect memory information
 pub fn collect_memory_info() -> Result<MemoryInfo> {
-    let (total_gb, used_gb) = read_memory_info()?;
-    let formatted = format!("{:.2} GB / {:.2} GB", used_gb, total_gb);
+    read_memory_info("MemTotal:", "MemAvailable:")
+}
 
-    Ok(MemoryInfo {
-        used_gb,
-        total_gb,
-        formatted,
-    })
+/// Collect swap information
+pub fn collect_swap_info() -> Result<MemoryInfo> {
+    read_memory_info("SwapTotal:", "SwapFree:")
 }
 
 /// Collect disk information
 pub fn collect_disk_info() -> Result<DiskInfo> {
-    Ok(DiskInfo {
-        usage: get_disk_usage()?,
-    })
+    get_disk_usage()
+}
+
+/// CPU model and temperature
+pub struct CpuCollector;
+
+impl Collector for CpuCollector {
+    type Output = CpuInfo;
+
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["cpu", "cpu_temp"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<CpuInfo> {
+        collect_cpu_info()
+    }
+
+    fn field(&self, cpu: &CpuInfo, name: &str) -> Option<Value> {
+        match name {
+            "cpu" => Some(Value::Text(cpu.brand.clone())),
+            "cpu_temp" => Some(
+                cpu.temperature
+                    .map_or(Value::Missing("Unknown"), Value::Celsius),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Every GPU, the primary one first
+pub struct GpuCollector;
+
+impl Collector for GpuCollector {
+    type Output = GpuInfo;
+
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["gpu", "gpu1", "gpu2", "gpu3"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<GpuInfo> {
+        collect_gpu_info()
+    }
+
+    fn field(&self, gpu: &GpuInfo, name: &str) -> Option<Value> {
+        let nth = |index: usize, missing: &'static str| {
+            gpu.all_gpus
+                .get(index)
+                .map_or(Value::Missing(missing), |gpu| Value::Gpu(gpu.clone()))
+        };
+        match name {
+            "gpu" => Some(nth(0, "Unknown GPU")),
+            "gpu1" => Some(nth(0, "No GPU")),
+            "gpu2" => Some(nth(1, "No secondary GPU")),
+            "gpu3" => Some(nth(2, "No third GPU")),
+            _ => None,
+        }
+    }
+}
+
+/// Memory usage
+pub struct MemoryCollector;
+
+impl Collector for MemoryCollector {
+    type Output = MemoryInfo;
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["memory"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<MemoryInfo> {
+        collect_memory_info()
+    }
+
+    fn field(&self, memory: &MemoryInfo, name: &str) -> Option<Value> {
+        match name {
+            "memory" => Some(Value::Memory(*memory)),
+            _ => None,
+        }
+    }
+}
+
+/// Swap usage
+pub struct SwapCollector;
+
+impl Collector for SwapCollector {
+    type Output = MemoryInfo;
+
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["swap"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<MemoryInfo> {
+        collect_swap_info()
+    }
+
+    fn field(&self, swap: &MemoryInfo, name: &str) -> Option<Value> {
+        match name {
+            "swap" if swap.total_bytes == 0 => Some(Value::Missing("No swap")),
+            "swap" => Some(Value::Memory(*swap)),
+            _ => None,
+        }
+    }
+}
+
+/// Usage of the root filesystem
+pub struct DiskCollector;
+
+impl Collector for DiskCollector {
+    type Output = DiskInfo;
+
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["disk_usage"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<DiskInfo> {
+        collect_disk_info()
+    }
+
+    fn field(&self, disk: &DiskInfo, name: &str) -> Option<Value> {
+        (name == "disk_usage").then_some(Value::Disk(*disk))
+    }
 }
 
 // Hardware detection functions
@@ -84,7 +201,9 @@ fn read_cpu_info() -> Result<String> {
     ))
 }
 
-fn read_memory_info() -> Result<(f64, f64)> {
+/// Usage from two `/proc/meminfo` lines, such as `MemTotal:` and
+/// `MemAvailable:`
+fn read_memory_info(total_key: &str, free_key: &str) -> Result<MemoryInfo> {
     use std::fs::File;
     use std::io::{BufRead, BufReader};
 
@@ -94,9 +213,8 @@ fn read_memory_info() -> Result<(f64, f64)> {
     let mut line = String::with_capacity(64);
 
     let mut total: f64 = 0.0;
-    let mut available: f64 = 0.0;
-    let mut found_total = false;
-    let mut found_available = false;
+    let mut free: f64 = 0.0;
+    let mut found = 0;
 
     loop {
         line.clear();
@@ -105,28 +223,27 @@ fn read_memory_info() -> Result<(f64, f64)> {
             break;
         }
 
-        if !found_total && line.starts_with("MemTotal") {
-            total = extract_memory_from_line(&line);
-            found_total = true;
-        } else if !found_available && line.starts_with("MemAvailable") {
-            available = extract_memory_from_line(&line);
-            found_available = true;
-        }
-
-        // Early exit if we found both
-        if found_total && found_available {
+        let target = if line.starts_with(total_key) {
+            &mut total
+        } else if line.starts_with(free_key) {
+            &mut free
+        } else {
+            continue;
+        };
+        *target = extract_memory_from_line(&line);
+        found += 1;
+
+        // Early exit once both are known
+        if found == 2 {
             break;
         }
     }
 
-    // Calculate used memory
-    let used = total - available;
-
-    // Convert to GB
-    let total_gb = total / 1024.0 / 1024.0;
-    let used_gb = used / 1024.0 / 1024.0;
-
-    Ok((total_gb, used_gb))
+    // The values are in kB
+    Ok(MemoryInfo {
+        used_bytes: ((total - free).max(0.0) * 1024.0) as u64,
+        total_bytes: (total * 1024.0) as u64,
+    })
 }
 
 fn extract_memory_from_line(line: &str) -> f64 {
@@ -137,7 +254,8 @@ fn extract_memory_from_line(line: &str) -> f64 {
     0.0
 }
 
-fn get_disk_usage() -> Result<String> {
+/// Usage of the root filesystem
+fn get_disk_usage() -> Result<DiskInfo> {
     use libc;
     use std::ffi::CString;
 
@@ -149,41 +267,27 @@ fn get_disk_usage() -> Result<String> {
         if libc::statvfs(path.as_ptr(), &mut stat) == 0 {
             let total_bytes = stat.f_blocks.wrapping_mul(stat.f_frsize as u64);
             let available_bytes = stat.f_bavail.wrapping_mul(stat.f_frsize as u64);
-            let used_bytes = total_bytes.saturating_sub(available_bytes);
-
-            let format_size = |bytes: u64| {
-                if bytes >= 1_000_000_000_000 {
-                    format!("{:.1}T", bytes as f64 / 1_000_000_000_000.0)
-                } else if bytes >= 1_000_000_000 {
-                    format!("{:.1}G", bytes as f64 / 1_000_000_000.0)
-                } else if bytes >= 1_000_000 {
-                    format!("{:.1}M", bytes as f64 / 1_000_000.0)
-                } else {
-                    format!("{}K", bytes / 1024)
-                }
-            };
-
-            let total = format_size(total_bytes);
-            let used = format_size(used_bytes);
-            let percent = if total_bytes > 0 {
-                ((used_bytes as f64 / total_bytes as f64) * 100.0) as u64
-            } else {
-                0
-            };
-
-            return Ok(format!("{} / {} ({}%)", used, total, percent));
+            return Ok(DiskInfo {
+                used_bytes: total_bytes.saturating_sub(available_bytes),
+                total_bytes,
+            });
         }
     }
 
-    // Fallback to df command if statvfs fails
-    let output = run_command("df", &["-h", "/"])?;
+    // Fallback to df command if statvfs fails, in POSIX format with 1K blocks
+    let output = run_command("df", &["-Pk", "/"])?;
     for line in output.lines().skip(1) {
         let parts: Vec<&str> = line.split_whitespace().collect();
         if parts.len() >= 4 {
-            let used = parts[2];
-            let total = parts[1];
-            let percentage = parts[4];
-            return Ok(format!("{} / {} ({})", used, total, percentage));
+            let kilobytes = |part: &str| part.parse::<u64>().ok().map(|kb| kb * 1024);
+            if let (Some(total_bytes), Some(available_bytes)) =
+                (kilobytes(parts[1]), kilobytes(parts[3]))
+            {
+                return Ok(DiskInfo {
+                    used_bytes: total_bytes.saturating_sub(available_bytes),
+                    total_bytes,
+                });
+            }
         }
     }
 
@@ -192,28 +296,28 @@ fn get_disk_usage() -> Result<String> {
     ))
 }
 
-fn detect_all_gpus() -> Result<Vec<String>> {
+fn detect_all_gpus() -> Result<Vec<Gpu>> {
     // Use direct sysfs reading (faster than lspci subprocess)
     let mut gpus = Vec::new();
 
     if let Ok(entries) = fs::read_dir("/sys/class/drm") {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with("card") && !name.contains("-") {
-                        // Read device name directly from sysfs
-                        if let Ok(device_name) = fs::read_to_string(path.join("device/name")) {
-                            let name = device_name.trim();
-                            if !name.is_empty() {
-                                // Determine if integrated or discrete
-                                let gpu_type = if is_integrated_gpu(&path) {
-                                    " [Integrated]"
-                                } else {
-                                    " [Discrete]"
-                                };
-                                gpus.push(format!("{}{}", name, gpu_type));
-                            }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with("card") && !name.contains("-") {
+                    // Read device name directly from sysfs
+                    if let Ok(device_name) = fs::read_to_string(path.join("device/name")) {
+                        let name = device_name.trim();
+                        if !name.is_empty() {
+                            let kind = if is_integrated_gpu(&path) {
+                                GpuKind::Integrated
+                            } else {
+                                GpuKind::Discrete
+                            };
+                            gpus.push(Gpu {
+                                name: name.to_string(),
+                                kind,
+                            });
                         }
                     }
                 }
@@ -223,11 +327,7 @@ fn detect_all_gpus() -> Result<Vec<String>> {
 
     if !gpus.is_empty() {
         // Sort GPUs: discrete first, then integrated
-        gpus.sort_by(|a, b| {
-            let a_integrated = a.contains("Integrated");
-            let b_integrated = b.contains("Integrated");
-            a_integrated.cmp(&b_integrated)
-        });
+        gpus.sort_by_key(|gpu| gpu.kind);
         return Ok(gpus);
     }
 
@@ -251,11 +351,7 @@ fn detect_all_gpus() -> Result<Vec<String>> {
             }
 
             if !gpus.is_empty() {
-                gpus.sort_by(|a, b| {
-                    let a_integrated = a.contains("Integrated");
-                    let b_integrated = b.contains("Integrated");
-                    a_integrated.cmp(&b_integrated)
-                });
+                gpus.sort_by_key(|gpu| gpu.kind);
                 return Ok(gpus);
             }
         }
@@ -282,7 +378,7 @@ fn is_integrated_gpu(card_path: &std::path::Path) -> bool {
     false
 }
 
-fn parse_gpu_from_lspci(line: &str) -> Option<String> {
+fn parse_gpu_from_lspci(line: &str) -> Option<Gpu> {
     if let Some(colon_pos) = line.rfind(':') {
         let gpu_part = line[colon_pos + 1..].trim();
 
@@ -291,9 +387,11 @@ fn parse_gpu_from_lspci(line: &str) -> Option<String> {
 
         // Try to extract the actual GPU name
         if let Some(gpu_name) = extract_gpu_model_name(cleaned) {
-            // Add [Discrete] or [Integrated] tag
-            let gpu_type = detect_gpu_type(line, &gpu_name);
-            return Some(format!("{}{}", gpu_name, gpu_type));
+            let kind = detect_gpu_type(line, &gpu_name);
+            return Some(Gpu {
+                name: gpu_name,
+                kind,
+            });
         }
     }
     None
@@ -430,7 +528,7 @@ fn parse_intel_gpu(description: &str) -> Option<String> {
     Some(format!("Intel {}", cleaned.trim()))
 }
 
-fn detect_gpu_type(lspci_line: &str, gpu_name: &str) -> String {
+fn detect_gpu_type(lspci_line: &str, gpu_name: &str) -> GpuKind {
     // Check if it's likely integrated
     let line_lower = lspci_line.to_lowercase();
     let name_lower = gpu_name.to_lowercase();
@@ -446,8 +544,8 @@ fn detect_gpu_type(lspci_line: &str, gpu_name: &str) -> String {
        // Intel integrated GPUs are usually on bus 00:02.0
        (lspci_line.starts_with("00:02.0") && line_lower.contains("intel"))
     {
-        " [Integrated]".to_string()
+        GpuKind::Integrated
     } else {
-        " [Discrete]".to_string()
+        GpuKind::Discrete
     }
 }
@@ -1,5 +1,6 @@
 pub mod desktop;
 pub mod hardware;
 pub mod packages;
+pub mod registry;
 pub mod sensors;
 pub mod system;
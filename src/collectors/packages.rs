@@ -1,6 +1,7 @@
 //! Package management information collection
 
-use crate::data::PackageInfo;
+use super::registry::{Collector, SystemInfo};
+use crate::data::{PackageInfo, Value};
 use crate::error::Result;
 use crate::utils::{command::*, file::*};
 use std::fs;
@@ -19,6 +20,33 @@ pub fn collect_package_info() -> Result<PackageInfo> {
     })
 }
 
+/// Counts of installed system and flatpak packages
+pub struct PackageCollector;
+
+impl Collector for PackageCollector {
+    type Output = PackageInfo;
+
+    fn name(&self) -> &'static str {
+        "packages"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["pkg_count", "flatpak_pkg_count"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<PackageInfo> {
+        collect_package_info()
+    }
+
+    fn field(&self, packages: &PackageInfo, name: &str) -> Option<Value> {
+        match name {
+            "pkg_count" => Some(Value::Count(packages.system_packages)),
+            "flatpak_pkg_count" => Some(Value::Count(packages.flatpak_packages)),
+            _ => None,
+        }
+    }
+}
+
 /// Supported package managers for different Linux distributions
 #[derive(Debug)]
 pub enum PackageManager {
@@ -46,7 +74,7 @@ pub fn get_flatpak_package_count() -> Result<usize> {
     let flatpak_dir = "/var/lib/flatpak/app";
 
     // Only check if directory exists to avoid unnecessary work
-    if !fs::metadata(flatpak_dir).is_ok() {
+    if fs::metadata(flatpak_dir).is_err() {
         return Ok(0);
     }
 
@@ -183,13 +211,11 @@ fn get_portage_package_count() -> Result<usize> {
     let mut count = 0;
 
     if let Ok(entries) = fs::read_dir("/var/db/pkg") {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    if let Ok(sub_entries) = fs::read_dir(&path) {
-                        count += sub_entries.count();
-                    }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Ok(sub_entries) = fs::read_dir(&path) {
+                    count += sub_entries.count();
                 }
             }
         }
@@ -0,0 +1,397 @@
+//! Collectors registered by name, run in dependency order and queried by the
+//! display through the fields they provide
+
+use crate::data::{
+    CpuInfo, DiskInfo, GpuInfo, MemoryInfo, OsInfo, PackageInfo, SystemStatus, UserInfo, Value,
+};
+use crate::error::{Result, SwiftfetchError};
+use rayon::prelude::*;
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+/// One source of system information.
+///
+/// A collector is run once, after the collectors named in `dependencies`,
+/// whose outputs it can read from `collected`. Its output is kept under
+/// `name` and the display reads it back through `field` for each item value
+/// listed in `fields`.
+pub trait Collector: Send + Sync + 'static {
+    type Output: Send + Sync + 'static;
+
+    fn name(&self) -> &'static str;
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Item values (`value = "..."` of `default` items) this collector provides
+    fn fields(&self) -> &'static [&'static str];
+
+    fn collect(&self, collected: &SystemInfo) -> Result<Self::Output>;
+
+    /// The field called `name`, one of `fields`, as a typed value
+    fn field(&self, output: &Self::Output, name: &str) -> Option<Value>;
+}
+
+type Output = Box<dyn Any + Send + Sync>;
+
+/// `Collector` without its output type, so collectors can share a registry
+trait DynCollector: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn dependencies(&self) -> &'static [&'static str];
+    fn fields(&self) -> &'static [&'static str];
+    fn collect(&self, collected: &SystemInfo) -> Result<Output>;
+    fn field(&self, output: &Output, name: &str) -> Option<Value>;
+}
+
+impl<C: Collector> DynCollector for C {
+    fn name(&self) -> &'static str {
+        Collector::name(self)
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        Collector::dependencies(self)
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        Collector::fields(self)
+    }
+
+    fn collect(&self, collected: &SystemInfo) -> Result<Output> {
+        Ok(Box::new(Collector::collect(self, collected)?))
+    }
+
+    fn field(&self, output: &Output, name: &str) -> Option<Value> {
+        Collector::field(self, output.downcast_ref()?, name)
+    }
+}
+
+/// Every collector swiftfetch can run
+#[derive(Default, Clone)]
+pub struct Registry {
+    collectors: Vec<Arc<dyn DynCollector>>,
+}
+
+impl Registry {
+    /// The built-in collectors. New collectors, including ones behind a
+    /// cargo feature, are registered here.
+    pub fn builtin() -> Registry {
+        use super::{hardware, packages, system};
+
+        let mut registry = Registry::default();
+        registry.register(system::OsCollector);
+        registry.register(system::StatusCollector);
+        registry.register(system::UserCollector);
+        registry.register(hardware::CpuCollector);
+        registry.register(hardware::GpuCollector);
+        registry.register(hardware::MemoryCollector);
+        registry.register(hardware::SwapCollector);
+        registry.register(hardware::DiskCollector);
+        registry.register(packages::PackageCollector);
+        registry
+    }
+
+    /// Add a collector, replacing any registered under the same name
+    pub fn register<C: Collector>(&mut self, collector: C) {
+        let name = Collector::name(&collector);
+        self.collectors
+            .retain(|registered| registered.name() != name);
+        self.collectors.push(Arc::new(collector));
+    }
+
+    /// Run every registered collector
+    pub fn collect_all(&self) -> SystemInfo {
+        let names: Vec<&str> = self.collectors.iter().map(|c| c.name()).collect();
+        self.collect(&names)
+    }
+
+    /// Run the collectors called `names` and the ones they depend on.
+    /// Collectors whose dependencies are done run in parallel.
+    pub fn collect(&self, names: &[&str]) -> SystemInfo {
+        let mut collected = SystemInfo::default();
+        for collector in &self.collectors {
+            for field in collector.fields() {
+                collected.providers.insert(field, collector.clone());
+            }
+        }
+
+        // The requested collectors plus everything they depend on
+        let mut pending: Vec<Arc<dyn DynCollector>> = Vec::new();
+        let mut queue: Vec<&str> = names.to_vec();
+        while let Some(name) = queue.pop() {
+            if pending.iter().any(|collector| collector.name() == name) {
+                continue;
+            }
+            match self.find(name) {
+                Some(collector) => {
+                    queue.extend(collector.dependencies());
+                    pending.push(collector.clone());
+                }
+                None => {
+                    collected.outputs.insert(
+                        name.to_string(),
+                        Err(SwiftfetchError::UnknownCollector(name.to_string())),
+                    );
+                }
+            }
+        }
+
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter().partition(|collector| {
+                collector
+                    .dependencies()
+                    .iter()
+                    .all(|dependency| collected.outputs.contains_key(*dependency))
+            });
+            if ready.is_empty() {
+                for collector in waiting {
+                    collected.outputs.insert(
+                        collector.name().to_string(),
+                        Err(SwiftfetchError::CircularDependency(
+                            collector.name().to_string(),
+                        )),
+                    );
+                }
+                break;
+            }
+
+            let outputs: Vec<_> = ready
+                .par_iter()
+                .map(|collector| (collector.name(), collector.collect(&collected)))
+                .collect();
+            for (name, output) in outputs {
+                collected.outputs.insert(name.to_string(), output);
+            }
+            pending = waiting;
+        }
+        collected
+    }
+
+    fn find(&self, name: &str) -> Option<&Arc<dyn DynCollector>> {
+        self.collectors
+            .iter()
+            .find(|collector| collector.name() == name)
+    }
+}
+
+/// Everything a `Registry::collect` run gathered. The built-in parts have
+/// typed accessors, which are `None` when their collector failed or was not
+/// run; other collectors' outputs are read with `get`.
+#[derive(Default)]
+pub struct SystemInfo {
+    outputs: HashMap<String, Result<Output>>,
+    /// The collector behind each field name
+    providers: HashMap<&'static str, Arc<dyn DynCollector>>,
+}
+
+impl SystemInfo {
+    /// Output of the collector called `name`, or the error it failed with
+    pub fn get<T: 'static>(&self, name: &str) -> Result<&T> {
+        match self.outputs.get(name) {
+            Some(Ok(output)) => output
+                .downcast_ref()
+                .ok_or_else(|| SwiftfetchError::OutputType(name.to_string())),
+            Some(Err(err)) => Err(err.clone()),
+            None => Err(SwiftfetchError::NotCollected(name.to_string())),
+        }
+    }
+
+    pub fn os(&self) -> Option<&OsInfo> {
+        self.get("os").ok()
+    }
+
+    pub fn user(&self) -> Option<&UserInfo> {
+        self.get("user").ok()
+    }
+
+    pub fn status(&self) -> Option<&SystemStatus> {
+        self.get("status").ok()
+    }
+
+    pub fn cpu(&self) -> Option<&CpuInfo> {
+        self.get("cpu").ok()
+    }
+
+    pub fn gpu(&self) -> Option<&GpuInfo> {
+        self.get("gpu").ok()
+    }
+
+    pub fn memory(&self) -> Option<&MemoryInfo> {
+        self.get("memory").ok()
+    }
+
+    pub fn swap(&self) -> Option<&MemoryInfo> {
+        self.get("swap").ok()
+    }
+
+    pub fn disk(&self) -> Option<&DiskInfo> {
+        self.get("disk").ok()
+    }
+
+    pub fn packages(&self) -> Option<&PackageInfo> {
+        self.get("packages").ok()
+    }
+
+    /// Collectors that failed, with their errors
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &SwiftfetchError)> {
+        self.outputs
+            .iter()
+            .filter_map(|(name, output)| Some((name.as_str(), output.as_ref().err()?)))
+    }
+
+    /// Whether some registered collector provides the field `name`
+    pub fn provides(&self, name: &str) -> bool {
+        self.providers.contains_key(name)
+    }
+
+    /// The field `name` (an item's `value`), if its collector ran and
+    /// succeeded
+    pub fn field(&self, name: &str) -> Option<Value> {
+        let collector = self.providers.get(name)?;
+        let output = self.outputs.get(collector.name())?.as_ref().ok()?;
+        collector.field(output, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails with an I/O error
+    struct Failing;
+
+    impl Collector for Failing {
+        type Output = ();
+
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn fields(&self) -> &'static [&'static str] {
+            &[]
+        }
+
+        fn collect(&self, _collected: &SystemInfo) -> Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "no such file").into())
+        }
+
+        fn field(&self, _output: &(), _name: &str) -> Option<Value> {
+            None
+        }
+    }
+
+    /// Outputs one more than the largest output of its dependencies, so the
+    /// output is the stage it had to run in
+    struct Stage {
+        name: &'static str,
+        dependencies: &'static [&'static str],
+    }
+
+    impl Collector for Stage {
+        type Output = u32;
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn dependencies(&self) -> &'static [&'static str] {
+            self.dependencies
+        }
+
+        fn fields(&self) -> &'static [&'static str] {
+            &[]
+        }
+
+        fn collect(&self, collected: &SystemInfo) -> Result<u32> {
+            let mut stage = 0;
+            for dependency in self.dependencies {
+                stage = stage.max(*collected.get::<u32>(dependency)?);
+            }
+            Ok(stage + 1)
+        }
+
+        fn field(&self, _output: &u32, _name: &str) -> Option<Value> {
+            None
+        }
+    }
+
+    fn registry(stages: &[(&'static str, &'static [&'static str])]) -> Registry {
+        let mut registry = Registry::default();
+        for &(name, dependencies) in stages {
+            registry.register(Stage { name, dependencies });
+        }
+        registry
+    }
+
+    #[test]
+    fn dependencies_run_in_earlier_stages() {
+        let registry = registry(&[("c", &["b"]), ("b", &["a"]), ("a", &[])]);
+        let collected = registry.collect(&["c"]);
+        assert_eq!(*collected.get::<u32>("a").unwrap(), 1);
+        assert_eq!(*collected.get::<u32>("b").unwrap(), 2);
+        assert_eq!(*collected.get::<u32>("c").unwrap(), 3);
+        assert_eq!(collected.errors().count(), 0);
+    }
+
+    #[test]
+    fn only_requested_collectors_and_their_dependencies_run() {
+        let registry = registry(&[("a", &[]), ("b", &["a"]), ("c", &[])]);
+        let collected = registry.collect(&["b"]);
+        assert_eq!(*collected.get::<u32>("b").unwrap(), 2);
+        assert!(matches!(
+            collected.get::<u32>("c"),
+            Err(SwiftfetchError::NotCollected(name)) if name == "c"
+        ));
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let registry = registry(&[("a", &["b"]), ("b", &["a"]), ("c", &[])]);
+        let collected = registry.collect_all();
+        for name in ["a", "b"] {
+            assert!(matches!(
+                collected.get::<u32>(name),
+                Err(SwiftfetchError::CircularDependency(cycle)) if cycle == name
+            ));
+        }
+        assert_eq!(*collected.get::<u32>("c").unwrap(), 1);
+    }
+
+    #[test]
+    fn unknown_dependencies_are_reported() {
+        let registry = registry(&[("a", &["missing"])]);
+        let collected = registry.collect(&["a"]);
+        assert!(matches!(
+            collected.get::<u32>("missing"),
+            Err(SwiftfetchError::UnknownCollector(name)) if name == "missing"
+        ));
+        // The dependent still runs and sees the error
+        assert!(matches!(
+            collected.get::<u32>("a"),
+            Err(SwiftfetchError::UnknownCollector(_))
+        ));
+    }
+
+    #[test]
+    fn reading_another_type_is_an_error() {
+        let collected = registry(&[("a", &[])]).collect_all();
+        assert!(matches!(
+            collected.get::<String>("a"),
+            Err(SwiftfetchError::OutputType(name)) if name == "a"
+        ));
+    }
+
+    #[test]
+    fn get_returns_the_collector_error_unchanged() {
+        let mut registry = Registry::default();
+        registry.register(Failing);
+        let collected = registry.collect_all();
+
+        let err = collected.get::<()>("failing").unwrap_err();
+        assert!(
+            matches!(&err, SwiftfetchError::Io(io) if io.kind() == std::io::ErrorKind::NotFound)
+        );
+        assert_eq!(err.to_string(), "I/O error: no such file");
+        let errors: Vec<_> = collected.errors().map(|(name, _)| name).collect();
+        assert_eq!(errors, ["failing"]);
+    }
+}
@@ -1,51 +1,80 @@
 //! Sensors and hardware monitoring (battery, temperature, etc.)
 
-use crate::error::Result;
+use crate::data::{BatteryInfo, ChargingState};
 use std::fs;
 
-/// Get battery information if available
-#[allow(dead_code)]
-pub fn get_battery_info() -> Result<String> {
-    // Try to find battery information in /sys/class/power_supply/
-    if let Ok(entries) = fs::read_dir("/sys/class/power_supply") {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let name = entry.file_name();
-                let name_str = name.to_string_lossy();
-
-                // Look for BAT* entries (typical battery naming)
-                if name_str.starts_with("BAT") {
-                    let battery_path = entry.path();
-
-                    // Read capacity percentage
-                    let capacity_path = battery_path.join("capacity");
-                    let status_path = battery_path.join("status");
-
-                    if let (Ok(capacity), Ok(status)) = (
-                        fs::read_to_string(&capacity_path),
-                        fs::read_to_string(&status_path),
-                    ) {
-                        let capacity = capacity.trim();
-                        let status = status.trim();
+/// Charge and status of the first battery, if there is one
+pub fn get_battery() -> Option<BatteryInfo> {
+    // Batteries are the BAT* entries in /sys/class/power_supply/
+    let entries = fs::read_dir("/sys/class/power_supply").ok()?;
+    entries.flatten().find_map(|entry| {
+        if !entry.file_name().to_string_lossy().starts_with("BAT") {
+            return None;
+        }
+        let path = entry.path();
+        let capacity = fs::read_to_string(path.join("capacity")).ok()?;
+        let status = fs::read_to_string(path.join("status")).ok()?;
+        Some(BatteryInfo {
+            percent: capacity.trim().parse().unwrap_or(0.0),
+            state: ChargingState::from_sysfs(&status),
+        })
+    })
+}
 
-                        let status_icon = match status {
-                            "Charging" => "⚡",
-                            "Discharging" => "🔋",
-                            "Full" => "🔋",
-                            "Not charging" => "🔌",
-                            _ => "🔋",
-                        };
+/// hwmon drivers that report the CPU temperature, most specific first
+const CPU_HWMON_NAMES: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal", "acpitz"];
 
-                        return Ok(format!("{}% {}", capacity, status_icon));
-                    }
-                }
+/// CPU temperature in °C, from hwmon or else the kernel thermal zones
+pub fn get_cpu_temperature() -> Option<f64> {
+    let mut best: Option<(usize, f64)> = None;
+    if let Ok(entries) = fs::read_dir("/sys/class/hwmon") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(name) = fs::read_to_string(path.join("name")) else {
+                continue;
+            };
+            let Some(rank) = CPU_HWMON_NAMES.iter().position(|n| *n == name.trim()) else {
+                continue;
+            };
+            if best.is_some_and(|(best_rank, _)| best_rank <= rank) {
+                continue;
+            }
+            if let Some(celsius) = read_millidegrees(&path.join("temp1_input")) {
+                best = Some((rank, celsius));
             }
         }
     }
+    if let Some((_, celsius)) = best {
+        return Some(celsius);
+    }
+
+    // Thermal zones: prefer one typed as the CPU package, else the first
+    let mut fallback = None;
+    let entries = fs::read_dir("/sys/class/thermal").ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with("thermal_zone")
+        {
+            continue;
+        }
+        let zone_type = fs::read_to_string(path.join("type")).unwrap_or_default();
+        let Some(celsius) = read_millidegrees(&path.join("temp")) else {
+            continue;
+        };
+        if zone_type.contains("pkg") || zone_type.contains("cpu") {
+            return Some(celsius);
+        }
+        fallback.get_or_insert(celsius);
+    }
+    fallback
+}
 
-    Err(crate::error::SwiftfetchError::Detection(
-        "No battery found".to_string(),
-    ))
+fn read_millidegrees(path: &std::path::Path) -> Option<f64> {
+    let millidegrees: f64 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    Some(millidegrees / 1000.0)
 }
 
-// Future: CPU/GPU temperature functions can go here
+// Future: GPU temperature functions can go here
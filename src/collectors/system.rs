@@ -1,69 +1,55 @@
 //! System information collection (OS, kernel, uptime, etc.)
 
-use crate::data::{OsInfo, SystemStatus, UserInfo};
+use super::registry::{Collector, SystemInfo};
+use crate::data::{OsInfo, SystemStatus, UserInfo, Value};
 use crate::error::Result;
 use crate::utils::{command::*, file::*};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Collect OS-related information (parallelized for speed)
 pub fn collect_os_info() -> Result<OsInfo> {
-    // Collect OS name, kernel version, and age in parallel
-    let ((name, kernel_version), age) = rayon::join(
-        || rayon::join(|| read_os_name(), || read_kernel_version()),
-        || get_os_age(),
+    // Collect OS name, kernel version, and install time in parallel
+    let ((os_release, kernel_version), installed) = rayon::join(
+        || rayon::join(read_os_release, read_kernel_version),
+        get_install_time,
     );
+    let (name, home_url, bug_report_url) = os_release?;
 
     Ok(OsInfo {
-        name: name?,
+        name,
+        home_url,
+        bug_report_url,
         kernel_version: kernel_version?,
-        age: age?,
+        installed,
     })
 }
 
 /// Collect user and environment information
 pub fn collect_user_info() -> Result<UserInfo> {
-    let username = get_username();
-    let hostname = get_hostname()?;
-    let formatted_user_info = format!("\x1b[1m{}@{}\x1b[0m", username, hostname.trim());
-
     Ok(UserInfo {
-        username,
-        hostname,
-        formatted_user_info,
+        username: get_username(),
+        hostname: get_hostname()?,
     })
 }
 
 /// Collect system status information (parallelized for speed)
 pub fn collect_system_status() -> Result<SystemStatus> {
     let uptime_seconds = read_uptime()?;
-    let uptime_formatted = crate::utils::parsing::format_uptime(uptime_seconds);
 
     // Collect independent status info in parallel
-    let ((init_system, battery_info), (desktop_environment, (editor, (shell, terminal)))) =
-        rayon::join(
-            || {
-                rayon::join(
-                    || detect_init_system(),
-                    || get_battery_info().unwrap_or_else(|_| "No battery".to_string()),
-                )
-            },
-            || {
-                rayon::join(
-                    || crate::collectors::desktop::detect_wm_or_de(),
-                    || {
-                        rayon::join(
-                            || get_editor(),
-                            || rayon::join(|| get_shell(), || get_terminal()),
-                        )
-                    },
-                )
-            },
-        );
+    let ((init_system, battery), (desktop_environment, (editor, (shell, terminal)))) = rayon::join(
+        || rayon::join(detect_init_system, crate::collectors::sensors::get_battery),
+        || {
+            rayon::join(crate::collectors::desktop::detect_wm_or_de, || {
+                rayon::join(get_editor, || rayon::join(get_shell, get_terminal))
+            })
+        },
+    );
 
     Ok(SystemStatus {
         uptime_seconds,
-        uptime_formatted,
         init_system,
-        battery_info,
+        battery,
         desktop_environment,
         editor,
         shell,
@@ -71,34 +57,135 @@ pub fn collect_system_status() -> Result<SystemStatus> {
     })
 }
 
-// Individual functions
-fn read_os_name() -> Result<String> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
-
-    // Read line by line to find PRETTY_NAME early (optimized with early exit)
-    let file = File::open("/etc/os-release")?;
-    let mut reader = BufReader::new(file);
-    let mut line = String::with_capacity(128);
-
-    loop {
-        line.clear();
-        let bytes_read = reader.read_line(&mut line)?;
-        if bytes_read == 0 {
-            break;
+/// Distribution name and links, kernel version and install age
+pub struct OsCollector;
+
+impl Collector for OsCollector {
+    type Output = OsInfo;
+
+    fn name(&self) -> &'static str {
+        "os"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["os", "kernel", "os_age"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<OsInfo> {
+        collect_os_info()
+    }
+
+    fn field(&self, os: &OsInfo, name: &str) -> Option<Value> {
+        match name {
+            "os" => Some(Value::Text(os.name.clone())),
+            "kernel" => Some(Value::Text(os.kernel_version.clone())),
+            "os_age" => Some(
+                os.installed
+                    .map_or(Value::Missing("Unsupported"), Value::Timestamp),
+            ),
+            _ => None,
         }
-        if line.starts_with("PRETTY_NAME") {
-            // Extract value without unnecessary allocations
-            if let Some(start) = line.find('=') {
-                let value = &line[start + 1..];
-                let trimmed = value.trim().trim_matches('"');
-                return Ok(trimmed.to_string());
-            }
+    }
+}
+
+/// Uptime, init system, battery and the session's programs
+pub struct StatusCollector;
+
+impl Collector for StatusCollector {
+    type Output = SystemStatus;
+
+    fn name(&self) -> &'static str {
+        "status"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &[
+            "uptime_seconds",
+            "init_system",
+            "battery",
+            "wm",
+            "editor",
+            "shell",
+            "terminal",
+        ]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<SystemStatus> {
+        collect_system_status()
+    }
+
+    fn field(&self, status: &SystemStatus, name: &str) -> Option<Value> {
+        let text = |text: &String| Some(Value::Text(text.clone()));
+        match name {
+            "uptime_seconds" => Some(Value::Seconds(status.uptime_seconds)),
+            "init_system" => text(&status.init_system),
+            "battery" => Some(
+                status
+                    .battery
+                    .map_or(Value::Missing("No battery"), Value::Battery),
+            ),
+            "wm" => text(&status.desktop_environment),
+            "editor" => text(&status.editor),
+            "shell" => text(&status.shell),
+            "terminal" => text(&status.terminal),
+            _ => None,
         }
     }
-    Err(crate::error::SwiftfetchError::Detection(
-        "OS name not found".to_string(),
-    ))
+}
+
+/// User name and host name
+pub struct UserCollector;
+
+impl Collector for UserCollector {
+    type Output = UserInfo;
+
+    fn name(&self) -> &'static str {
+        "user"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["username", "hostname", "user_info"]
+    }
+
+    fn collect(&self, _: &SystemInfo) -> Result<UserInfo> {
+        collect_user_info()
+    }
+
+    fn field(&self, user: &UserInfo, name: &str) -> Option<Value> {
+        match name {
+            "username" => Some(Value::Text(user.username.clone())),
+            "hostname" => Some(Value::Text(user.hostname.clone())),
+            "user_info" => Some(Value::User(user.clone())),
+            _ => None,
+        }
+    }
+}
+
+// Individual functions
+
+/// `PRETTY_NAME`, `HOME_URL` and `BUG_REPORT_URL` from os-release
+fn read_os_release() -> Result<(String, Option<String>, Option<String>)> {
+    let os_release = std::fs::read_to_string("/etc/os-release")?;
+    let (mut name, mut home_url, mut bug_report_url) = (None, None, None);
+    for line in os_release.lines() {
+        let Some((field, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        let slot = match field {
+            "PRETTY_NAME" => &mut name,
+            "HOME_URL" => &mut home_url,
+            "BUG_REPORT_URL" => &mut bug_report_url,
+            _ => continue,
+        };
+        if !value.is_empty() {
+            *slot = Some(value.to_string());
+        }
+    }
+
+    let name = name
+        .ok_or_else(|| crate::error::SwiftfetchError::Detection("OS name not found".to_string()))?;
+    Ok((name, home_url, bug_report_url))
 }
 
 fn read_kernel_version() -> Result<String> {
@@ -125,10 +212,10 @@ fn read_uptime() -> Result<u64> {
     Ok(secs as u64)
 }
 
-fn get_os_age() -> Result<String> {
+/// Birth time of the root filesystem, roughly when the system was installed
+fn get_install_time() -> Option<SystemTime> {
     use libc;
     use std::ffi::CString;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
     // Use statx syscall to get birth time directly (faster than stat command)
     #[cfg(target_os = "linux")]
@@ -149,48 +236,22 @@ fn get_os_age() -> Result<String> {
                 &mut statx_buf as *mut _ as *mut libc::c_void,
             );
 
-            if result == 0 && (statx_buf.stx_mask & mask) != 0 {
-                let birth_sec = statx_buf.stx_btime.tv_sec as i64;
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as i64;
-
-                if birth_sec > 0 {
-                    let age_days = (now - birth_sec) / 86400;
-                    if age_days == 1 {
-                        return Ok("1 day".to_string());
-                    } else {
-                        return Ok(format!("{} days", age_days));
-                    }
-                }
+            if result == 0 && (statx_buf.stx_mask & mask) != 0 && statx_buf.stx_btime.tv_sec > 0 {
+                return Some(UNIX_EPOCH + Duration::from_secs(statx_buf.stx_btime.tv_sec as u64));
             }
         }
     }
 
     // Fallback to stat command if statx fails or not available
-    let stat_output = run_command("stat", &["-c", "%W", "/"])?;
-    let birth_timestamp: i64 = stat_output.trim().parse().unwrap_or(0);
-
-    if birth_timestamp > 0 {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        let age_days = (now - birth_timestamp) / 86400;
-
-        if age_days == 1 {
-            Ok("1 day".to_string())
-        } else {
-            Ok(format!("{} days", age_days))
-        }
-    } else {
-        Ok("Unsupported".to_string())
-    }
+    let stat_output = run_command("stat", &["-c", "%W", "/"]).ok()?;
+    let birth_timestamp: u64 = stat_output.trim().parse().unwrap_or(0);
+    (birth_timestamp > 0).then(|| UNIX_EPOCH + Duration::from_secs(birth_timestamp))
 }
 
 fn get_hostname() -> Result<String> {
-    read_first_line("/proc/sys/kernel/hostname")
+    Ok(read_first_line("/proc/sys/kernel/hostname")?
+        .trim()
+        .to_string())
 }
 
 fn get_username() -> String {
@@ -295,7 +356,7 @@ fn get_shell() -> String {
     std::env::var("SHELL")
         .unwrap_or_else(|_| "Unknown".to_string())
         .split('/')
-        .last()
+        .next_back()
         .unwrap_or("Unknown")
         .to_string()
 }
@@ -342,48 +403,3 @@ fn detect_init_system() -> String {
 
     "Unknown".to_string()
 }
-
-fn get_battery_info() -> Result<String> {
-    use std::fs;
-
-    // Try to find battery information in /sys/class/power_supply/
-    if let Ok(entries) = fs::read_dir("/sys/class/power_supply") {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let name = entry.file_name();
-                let name_str = name.to_string_lossy();
-
-                // Look for BAT* entries (typical battery naming)
-                if name_str.starts_with("BAT") {
-                    let battery_path = entry.path();
-
-                    // Read capacity percentage
-                    let capacity_path = battery_path.join("capacity");
-                    let status_path = battery_path.join("status");
-
-                    if let (Ok(capacity), Ok(status)) = (
-                        fs::read_to_string(&capacity_path),
-                        fs::read_to_string(&status_path),
-                    ) {
-                        let capacity = capacity.trim();
-                        let status = status.trim();
-
-                        let status_icon = match status {
-                            "Charging" => "⚡",
-                            "Discharging" => "🔋",
-                            "Full" => "🔋",
-                            "Not charging" => "🔌",
-                            _ => "🔋",
-                        };
-
-                        return Ok(format!("{}% {}", capacity, status_icon));
-                    }
-                }
-            }
-        }
-    }
-
-    Err(crate::error::SwiftfetchError::Detection(
-        "No battery found".to_string(),
-    ))
-}
@@ -1,11 +1,21 @@
+use crate::error::{Result, SwiftfetchError};
+use crate::theme;
+use crate::warnings::warn;
 use dirs::config_dir;
 use serde::Deserialize;
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
 
 #[derive(Deserialize, Debug)]
 pub struct Config {
+    /// Palette loaded underneath `colors`, see `crate::theme`
+    pub theme: Option<String>,
     pub display: DisplayConfig,
-    pub colors: HashMap<String, String>,
+    #[serde(default)]
+    pub colors: HashMap<String, ColorSpec>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -13,9 +23,25 @@ pub struct DisplayConfig {
     pub items: Vec<ConfigEntry>,
     pub separator: Option<String>,
     pub ascii_path: Option<String>,
-    pub ascii_color: String,
+    pub ascii_color: ColorSpec,
     pub use_default_ascii: Option<bool>,
     pub show_all_gpus: Option<bool>,
+    pub color: Option<String>,
+    pub color_depth: Option<String>,
+    pub overflow: Option<String>,
+    pub bar_width: Option<usize>,
+    pub bar_full: Option<String>,
+    pub bar_empty: Option<String>,
+    pub bar_border: Option<String>,
+    pub bar_color_full: Option<ColorSpec>,
+    pub bar_color_empty: Option<ColorSpec>,
+    pub section_border: Option<String>,
+    /// Number of columns to balance the items across
+    pub columns: Option<usize>,
+    /// OSC 8 hyperlinks on values: `auto`, `always` or `never`
+    pub hyperlinks: Option<String>,
+    /// Align keys to the widest one in their list: `left`, `right` or `none`
+    pub key_align: Option<String>,
     #[serde(alias = "artwork_mode")]
     pub display_mode: Option<String>,
     #[serde(alias = "kitty_image_path")]
@@ -32,49 +58,256 @@ pub struct DisplayConfig {
     pub image_offset_columns: Option<i32>,
     #[serde(alias = "kitty_offset_rows")]
     pub image_offset_rows: Option<i32>,
+    pub image_protocols: Option<Vec<String>>,
+    pub image_unicode_placeholders: Option<bool>,
+    pub image_transmission: Option<String>,
+    pub image_animate: Option<bool>,
+    pub image_ascii_width: Option<u32>,
+    pub image_ascii_charset: Option<String>,
+    pub image_ascii_colored: Option<bool>,
+    pub image_ascii_dither: Option<bool>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct ConfigEntry {
+    #[serde(default)]
     pub key: String,
     #[serde(rename = "type")]
     pub r#type: String,
+    #[serde(default)]
     pub value: String,
-    pub color: Option<String>,
-    pub value_color: Option<String>,
+    pub color: Option<ColorSpec>,
+    pub value_color: Option<ColorSpec>,
+    pub overflow: Option<String>,
+    /// Block drawn for each color of a `colors` item
+    pub symbol: Option<String>,
+    /// How many times `symbol` is repeated per color, or the number of
+    /// cells in a usage bar
+    pub width: Option<usize>,
+    /// Where a usage bar goes relative to the value: `after`, `before`,
+    /// `only` or `none`
+    pub bar: Option<String>,
+    /// Value colors by range of the underlying number, first match wins
+    pub thresholds: Option<Vec<Threshold>>,
+    /// Children of a `section` item
+    pub items: Option<Vec<ConfigEntry>>,
+    /// Border style of a `section`: `single`, `double`, `rounded` or `ascii`
+    pub border: Option<String>,
+    /// Info column (from 1) this item starts; later items follow it there
+    pub column: Option<usize>,
+    /// Overrides `display.separator` for this item
+    pub separator: Option<String>,
+    /// Text before the key, in the key's color
+    pub prefix: Option<String>,
+    /// Text after the value, in the value's color
+    pub suffix: Option<String>,
+    /// Columns the key and separator take at least, padded like `key_align`
+    pub min_width: Option<usize>,
+    /// Hyperlink target of the value, with `{value}`, `{home_url}` and
+    /// `{bug_report_url}` filled in; `""` turns off the automatic link
+    pub link: Option<String>,
+}
+
+/// A color as written in the config: a `[colors]` key, a color name or hex
+/// color with optional styles (`"bold italic #ff8800 on #202020"`), the
+/// same as a table (`{ fg = "cyan", bg = "black", bold = true }`), or a
+/// gradient (`{ gradient = ["#f38ba8", "#89b4fa"], direction = "vertical" }`)
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ColorSpec {
+    Spec(String),
+    // Before `Table`, whose fields are all optional and would match anything
+    Gradient {
+        gradient: Vec<String>,
+        /// `horizontal` (default) or `vertical`
+        direction: Option<String>,
+    },
+    Table {
+        fg: Option<String>,
+        bg: Option<String>,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        dim: bool,
+        #[serde(default)]
+        italic: bool,
+        #[serde(default)]
+        underline: bool,
+        #[serde(default)]
+        reverse: bool,
+        #[serde(default)]
+        strikethrough: bool,
+    },
+}
+
+impl ColorSpec {
+    /// Names of the text attributes switched on in a table
+    pub fn attributes(&self) -> Vec<&'static str> {
+        match self {
+            ColorSpec::Spec(_) | ColorSpec::Gradient { .. } => Vec::new(),
+            ColorSpec::Table {
+                bold,
+                dim,
+                italic,
+                underline,
+                reverse,
+                strikethrough,
+                ..
+            } => [
+                ("bold", bold),
+                ("dim", dim),
+                ("italic", italic),
+                ("underline", underline),
+                ("reverse", reverse),
+                ("strikethrough", strikethrough),
+            ]
+            .into_iter()
+            .filter(|(_, on)| **on)
+            .map(|(name, _)| name)
+            .collect(),
+        }
+    }
+}
+
+/// Tables are shown in the equivalent string form
+impl fmt::Display for ColorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorSpec::Spec(spec) => f.write_str(spec),
+            ColorSpec::Gradient {
+                gradient,
+                direction,
+            } => {
+                write!(f, "gradient {}", gradient.join(" "))?;
+                match direction {
+                    Some(direction) => write!(f, " ({})", direction),
+                    None => Ok(()),
+                }
+            }
+            ColorSpec::Table { fg, bg, .. } => {
+                let mut words = self.attributes();
+                words.extend(fg.as_deref());
+                if let Some(bg) = bg {
+                    words.push("on");
+                    words.push(bg);
+                }
+                f.write_str(&words.join(" "))
+            }
+        }
+    }
+}
+
+/// Colors a value whose number lies in `min..max` (either bound optional)
+#[derive(Deserialize, Debug, Clone)]
+pub struct Threshold {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub color: ColorSpec,
+}
+
+impl Threshold {
+    pub fn contains(&self, number: f64) -> bool {
+        self.min.is_none_or(|min| number >= min) && self.max.is_none_or(|max| number < max)
+    }
 }
 
-pub fn ensure_user_config_exists() {
+/// Copy the system config to the user's config dir if they have none yet,
+/// returning where it was created
+pub fn ensure_user_config_exists() -> Result<Option<PathBuf>> {
     let user_config_path = config_dir()
         .map(|p| p.join("swiftfetch/config.toml"))
-        .expect("Could not determine config dir");
+        .ok_or_else(|| SwiftfetchError::Config("Could not determine config dir".to_string()))?;
 
-    if !user_config_path.exists() {
-        let default_config_path = "/usr/share/swiftfetch/config.toml";
+    if user_config_path.exists() {
+        return Ok(None);
+    }
 
-        if let Some(parent) = user_config_path.parent() {
-            fs::create_dir_all(parent).unwrap();
-        }
+    let default_config_path = "/usr/share/swiftfetch/config.toml";
+    if !Path::new(default_config_path).exists() {
+        warn(format!("Missing default config at {}", default_config_path));
+        return Ok(None);
+    }
 
-        if Path::new(default_config_path).exists() {
-            fs::copy(default_config_path, &user_config_path).unwrap();
-            println!("Created config at {}", user_config_path.display());
-        } else {
-            eprintln!("Missing default config at {}", default_config_path);
-        }
+    if let Some(parent) = user_config_path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    fs::copy(default_config_path, &user_config_path)?;
+    Ok(Some(user_config_path))
 }
 
-pub fn load_config() -> Config {
+/// The user's config, falling back to the system one
+pub fn load_config() -> Result<Config> {
     let user_config_path = config_dir()
         .map(|p| p.join("swiftfetch/config.toml"))
         .unwrap_or_else(|| "config.toml".into());
+    let system_config_path = "/usr/share/swiftfetch/config.toml";
 
     // Try user config first, then fallback to system config
     // Use read_to_string which will fail fast if file doesn't exist
     let config_data = fs::read_to_string(&user_config_path)
-        .or_else(|_| fs::read_to_string("/usr/share/swiftfetch/config.toml"))
-        .expect("Failed to read config file");
+        .or_else(|_| fs::read_to_string(system_config_path))
+        .map_err(|err| {
+            SwiftfetchError::Config(format!(
+                "Failed to read {} or {}: {}",
+                user_config_path.display(),
+                system_config_path,
+                err
+            ))
+        })?;
+
+    parse_config(&config_data)
+}
+
+/// Config from the contents of a `config.toml`, with its theme applied
+pub fn parse_config(data: &str) -> Result<Config> {
+    let mut config: Config =
+        toml::de::from_str(data).map_err(|err| SwiftfetchError::Config(err.to_string()))?;
+
+    // Inline colors override individual keys of the theme
+    if let Some(name) = &config.theme {
+        match theme::load(name) {
+            Some(mut colors) => {
+                colors.extend(config.colors.drain());
+                config.colors = colors;
+            }
+            None => warn(format!(
+                "Unknown theme '{}', see swiftfetch --list-themes; using [colors] only",
+                name
+            )),
+        }
+    }
 
-    toml::de::from_str(&config_data).expect("Failed to parse config file")
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(range: &str) -> Threshold {
+        toml::de::from_str(&format!("color = \"red\"\n{}", range)).unwrap()
+    }
+
+    #[test]
+    fn threshold_includes_min_and_excludes_max() {
+        let threshold = threshold("min = 70\nmax = 85");
+        assert!(threshold.contains(70.0));
+        assert!(threshold.contains(84.9));
+        assert!(!threshold.contains(85.0));
+        assert!(!threshold.contains(69.9));
+    }
+
+    #[test]
+    fn threshold_with_an_open_end() {
+        let above = threshold("min = 90");
+        assert!(above.contains(90.0));
+        assert!(above.contains(1e9));
+        assert!(!above.contains(89.9));
+
+        let below = threshold("max = 10");
+        assert!(below.contains(-5.0));
+        assert!(!below.contains(10.0));
+
+        assert!(threshold("").contains(0.0));
+    }
 }
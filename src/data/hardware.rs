@@ -1,41 +1,70 @@
 //! Hardware-specific information structures
 
-/// Hardware information
-#[derive(Debug, Clone)]
-pub struct HardwareInfo {
-    pub cpu: CpuInfo,
-    pub gpu: GpuInfo,
-    pub memory: MemoryInfo,
-    pub disk: DiskInfo,
-}
-
 /// CPU information
 #[derive(Debug, Clone)]
 pub struct CpuInfo {
     pub brand: String,
-    // Future: temperature, cores, frequency
+    /// Temperature in °C, when a sensor is readable
+    pub temperature: Option<f64>,
+    // Future: cores, frequency
 }
 
 /// GPU information with support for multiple GPUs
 #[derive(Debug, Clone)]
 pub struct GpuInfo {
-    pub primary: String,
-    pub all_gpus: Vec<String>,
+    /// Every GPU, the primary one first
+    pub all_gpus: Vec<Gpu>,
+}
+
+impl GpuInfo {
+    pub fn primary(&self) -> Option<&Gpu> {
+        self.all_gpus.first()
+    }
 }
 
-/// Memory information
 #[derive(Debug, Clone)]
+pub struct Gpu {
+    /// Model name, e.g. `AMD Radeon RX 7800 XT`
+    pub name: String,
+    pub kind: GpuKind,
+}
+
+/// Whether a GPU is a card of its own or part of the CPU. Discrete GPUs
+/// sort first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GpuKind {
+    Discrete,
+    Integrated,
+}
+
+/// Memory or swap usage in bytes
+#[derive(Debug, Clone, Copy)]
 pub struct MemoryInfo {
-    #[allow(dead_code)]
-    pub used_gb: f64,
-    #[allow(dead_code)]
-    pub total_gb: f64,
-    pub formatted: String,
+    pub used_bytes: u64,
+    pub total_bytes: u64,
 }
 
-/// Disk usage information
-#[derive(Debug, Clone)]
+/// Disk usage of the root filesystem in bytes
+#[derive(Debug, Clone, Copy)]
 pub struct DiskInfo {
-    pub usage: String,
+    pub used_bytes: u64,
+    pub total_bytes: u64,
     // Future: individual partition info
 }
+
+/// Share of `total` in use, `None` when the total is zero
+fn usage_percent(used: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| used as f64 / total as f64 * 100.0)
+}
+
+impl MemoryInfo {
+    pub fn percent(&self) -> Option<f64> {
+        usage_percent(self.used_bytes, self.total_bytes)
+    }
+}
+
+impl DiskInfo {
+    pub fn percent(&self) -> Option<f64> {
+        usage_percent(self.used_bytes, self.total_bytes)
+    }
+}
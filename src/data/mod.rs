@@ -1,5 +1,7 @@
 pub mod hardware;
 pub mod system;
+pub mod value;
 
 pub use hardware::*;
 pub use system::*;
+pub use value::*;
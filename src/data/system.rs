@@ -1,23 +1,18 @@
 //! System-wide information structures
 
-use super::hardware::HardwareInfo;
-
-/// Complete system information gathered by swiftfetch
-#[derive(Debug, Clone)]
-pub struct SystemInfo {
-    pub os: OsInfo,
-    pub hardware: HardwareInfo,
-    pub packages: PackageInfo,
-    pub status: SystemStatus,
-    pub user: UserInfo,
-}
+use std::time::SystemTime;
 
 /// Operating system related information
 #[derive(Debug, Clone)]
 pub struct OsInfo {
     pub name: String,
+    /// `HOME_URL` from os-release
+    pub home_url: Option<String>,
+    /// `BUG_REPORT_URL` from os-release
+    pub bug_report_url: Option<String>,
     pub kernel_version: String,
-    pub age: String,
+    /// Creation time of the root filesystem, when the filesystem records it
+    pub installed: Option<SystemTime>,
 }
 
 /// User and session information
@@ -25,7 +20,6 @@ pub struct OsInfo {
 pub struct UserInfo {
     pub username: String,
     pub hostname: String,
-    pub formatted_user_info: String,
 }
 
 /// Package management information
@@ -38,13 +32,42 @@ pub struct PackageInfo {
 /// System status and runtime information
 #[derive(Debug, Clone)]
 pub struct SystemStatus {
-    #[allow(dead_code)]
     pub uptime_seconds: u64,
-    pub uptime_formatted: String,
     pub init_system: String,
-    pub battery_info: String,
+    /// `None` without a battery
+    pub battery: Option<BatteryInfo>,
     pub desktop_environment: String,
     pub editor: String,
     pub shell: String,
     pub terminal: String,
 }
+
+/// Charge of the first battery
+#[derive(Debug, Clone, Copy)]
+pub struct BatteryInfo {
+    pub percent: f64,
+    pub state: ChargingState,
+}
+
+/// Battery status as reported by the kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargingState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    Unknown,
+}
+
+impl ChargingState {
+    /// Parse the `status` attribute of a power supply
+    pub fn from_sysfs(status: &str) -> ChargingState {
+        match status.trim() {
+            "Charging" => ChargingState::Charging,
+            "Discharging" => ChargingState::Discharging,
+            "Full" => ChargingState::Full,
+            "Not charging" => ChargingState::NotCharging,
+            _ => ChargingState::Unknown,
+        }
+    }
+}
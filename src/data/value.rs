@@ -0,0 +1,26 @@
+//! Typed values of the fields items show, before any formatting
+
+use super::{BatteryInfo, DiskInfo, Gpu, MemoryInfo, UserInfo};
+use std::time::SystemTime;
+
+/// A field's value as collected; the display's formatter turns it into text
+#[derive(Debug, Clone)]
+pub enum Value {
+    Text(String),
+    Count(usize),
+    /// Memory or swap usage
+    Memory(MemoryInfo),
+    /// Filesystem usage
+    Disk(DiskInfo),
+    /// A duration such as the uptime
+    Seconds(u64),
+    /// A point in time, shown as the time since then
+    Timestamp(SystemTime),
+    Celsius(f64),
+    Battery(BatteryInfo),
+    Gpu(Gpu),
+    /// A user on a host, shown as `user@host`
+    User(UserInfo),
+    /// Nothing to show; the text says what is missing, e.g. `No swap`
+    Missing(&'static str),
+}
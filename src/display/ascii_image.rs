@@ -0,0 +1,185 @@
+//! Runtime conversion of raster images into (optionally colored) character art
+
+use crate::utils::cache::{self, CacheKey};
+use image::{imageops::FilterType, GenericImageView, Rgba};
+use std::path::Path;
+
+const CACHE_NAMESPACE: &str = "ascii";
+const DEFAULT_COLUMNS: u32 = 40;
+/// Ordered from "empty" to "full"; bright pixels get the densest glyphs.
+const DEFAULT_CHARSET: &str = " .:-=+*#%@";
+/// Terminal cells are roughly twice as tall as they are wide.
+const CELL_ASPECT: f32 = 0.5;
+const ALPHA_CUTOFF: u8 = 128;
+
+pub struct AsciiImageOptions<'a> {
+    pub columns: Option<u32>,
+    pub charset: Option<&'a str>,
+    pub colored: bool,
+    pub dither: bool,
+}
+
+/// Convert `path` into lines of character art, reusing a cached copy when the
+/// source file and options are unchanged.
+pub fn convert(path: &str, options: &AsciiImageOptions) -> Result<Vec<String>, String> {
+    let columns = options.columns.unwrap_or(DEFAULT_COLUMNS).max(1);
+    let charset: Vec<char> = options
+        .charset
+        .filter(|set| !set.is_empty())
+        .unwrap_or(DEFAULT_CHARSET)
+        .chars()
+        .collect();
+
+    let mut key = CacheKey::new();
+    key.push_file(Path::new(path))
+        .map_err(|err| format!("Failed to read image '{}': {}", path, err))?;
+    key.push_u64(columns as u64)
+        .push_str(&charset.iter().collect::<String>())
+        .push_u64(options.colored as u64)
+        .push_u64(options.dither as u64);
+    let cache_file = format!("{}.txt", key.to_hex());
+
+    if let Some(cached) = cache::read_entry(CACHE_NAMESPACE, &cache_file) {
+        if let Ok(text) = String::from_utf8(cached) {
+            return Ok(text.lines().map(String::from).collect());
+        }
+    }
+
+    let lines = render(path, columns, &charset, options)?;
+    cache::write_entry(CACHE_NAMESPACE, &cache_file, lines.join("\n").as_bytes());
+    Ok(lines)
+}
+
+fn render(
+    path: &str,
+    columns: u32,
+    charset: &[char],
+    options: &AsciiImageOptions,
+) -> Result<Vec<String>, String> {
+    let image = super::graphics::open_image(Path::new(path))?;
+
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err(format!("Image '{}' is empty", path));
+    }
+
+    let rows = ((height as f32 / width as f32) * columns as f32 * CELL_ASPECT)
+        .round()
+        .max(1.0) as u32;
+    let pixels = image
+        .resize_exact(columns, rows, FilterType::Triangle)
+        .to_rgba8();
+
+    // Brightness per cell in 0.0..=1.0; transparent cells are `None`
+    let mut brightness: Vec<Option<f32>> = pixels
+        .pixels()
+        .map(|pixel| (pixel[3] >= ALPHA_CUTOFF).then(|| luminance(pixel)))
+        .collect();
+
+    let levels = charset.len();
+    let mut glyph_indices = vec![0usize; brightness.len()];
+    for y in 0..rows as usize {
+        for x in 0..columns as usize {
+            let idx = y * columns as usize + x;
+            let Some(value) = brightness[idx] else {
+                continue;
+            };
+
+            let level = quantize(value, levels);
+            glyph_indices[idx] = level;
+
+            if options.dither && levels > 1 {
+                let error = value - level as f32 / (levels - 1) as f32;
+                diffuse(
+                    &mut brightness,
+                    columns as usize,
+                    rows as usize,
+                    x,
+                    y,
+                    error,
+                );
+            }
+        }
+    }
+
+    let mut lines = Vec::with_capacity(rows as usize);
+    for y in 0..rows {
+        let mut line = String::with_capacity(columns as usize * 4);
+        let mut last_color: Option<[u8; 3]> = None;
+
+        for x in 0..columns {
+            let idx = (y * columns + x) as usize;
+            let pixel = pixels.get_pixel(x, y);
+
+            if brightness[idx].is_none() {
+                line.push(' ');
+                continue;
+            }
+
+            if options.colored {
+                let rgb = [pixel[0], pixel[1], pixel[2]];
+                if last_color != Some(rgb) {
+                    line.push_str(&format!("\x1b[38;2;{};{};{}m", rgb[0], rgb[1], rgb[2]));
+                    last_color = Some(rgb);
+                }
+            }
+            line.push(charset[glyph_indices[idx]]);
+        }
+
+        if last_color.is_some() {
+            line.push_str("\x1b[0m");
+        }
+        lines.push(line);
+    }
+
+    trim_blank_rows(&mut lines);
+    Ok(lines)
+}
+
+fn luminance(pixel: &Rgba<u8>) -> f32 {
+    (0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32) / 255.0
+}
+
+fn quantize(value: f32, levels: usize) -> usize {
+    if levels <= 1 {
+        return 0;
+    }
+    ((value.clamp(0.0, 1.0) * (levels - 1) as f32).round() as usize).min(levels - 1)
+}
+
+/// Floyd–Steinberg error diffusion onto the not-yet-visited neighbours
+fn diffuse(
+    brightness: &mut [Option<f32>],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    error: f32,
+) {
+    let mut spread = |dx: isize, dy: usize, weight: f32| {
+        let nx = x as isize + dx;
+        let ny = y + dy;
+        if nx < 0 || nx as usize >= width || ny >= height {
+            return;
+        }
+        if let Some(value) = brightness[ny * width + nx as usize].as_mut() {
+            *value += error * weight;
+        }
+    };
+
+    spread(1, 0, 7.0 / 16.0);
+    spread(-1, 1, 3.0 / 16.0);
+    spread(0, 1, 5.0 / 16.0);
+    spread(1, 1, 1.0 / 16.0);
+}
+
+fn trim_blank_rows(lines: &mut Vec<String>) {
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let leading = lines
+        .iter()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    lines.drain(..leading);
+}
@@ -0,0 +1,154 @@
+//! Usage bars (`[■■■■■□□□□□]`) for percentage-valued fields
+
+use crate::config::DisplayConfig;
+use crate::warnings::warn;
+
+const DEFAULT_WIDTH: usize = 10;
+const DEFAULT_FULL: &str = "■";
+const DEFAULT_EMPTY: &str = "□";
+const DEFAULT_BORDER: &str = "[]";
+
+/// Where a bar goes relative to the value text
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Before,
+    After,
+    Only,
+}
+
+impl Placement {
+    /// Parse an item's `bar` option, `None` meaning no bar is drawn
+    pub fn from_config(name: Option<&str>) -> Option<Placement> {
+        match name? {
+            "after" => Some(Placement::After),
+            "before" => Some(Placement::Before),
+            "only" => Some(Placement::Only),
+            "none" => None,
+            other => {
+                warn(format!(
+                    "Unknown bar '{}' (expected after, before, only or none), drawing no bar",
+                    other
+                ));
+                None
+            }
+        }
+    }
+
+    /// Combine the value text with its bar
+    pub fn apply(self, value: &str, bar: &str) -> String {
+        match self {
+            Placement::Before => format!("{} {}", bar, value),
+            Placement::After => format!("{} {}", value, bar),
+            Placement::Only => bar.to_string(),
+        }
+    }
+}
+
+/// Draw a bar filled to `percent` (clamped to 0–100).
+///
+/// `full_color` and `empty_color` are SGR sequences for the two kinds of cell
+/// and `restore` re-applies the value style afterwards; all three are empty
+/// in plain output. `width` overrides `bar_width` for a single item.
+pub fn render(
+    percent: f64,
+    width: Option<usize>,
+    display: &DisplayConfig,
+    full_color: &str,
+    empty_color: &str,
+    restore: &str,
+) -> String {
+    let width = width.or(display.bar_width).unwrap_or(DEFAULT_WIDTH);
+    let full = display.bar_full.as_deref().unwrap_or(DEFAULT_FULL);
+    let empty = display.bar_empty.as_deref().unwrap_or(DEFAULT_EMPTY);
+
+    // The first character opens the bar and the rest closes it; a single
+    // character is used on both sides
+    let mut border = display
+        .bar_border
+        .as_deref()
+        .unwrap_or(DEFAULT_BORDER)
+        .chars();
+    let left: String = border.next().map(String::from).unwrap_or_default();
+    let mut right: String = border.collect();
+    if right.is_empty() {
+        right = left.clone();
+    }
+
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    format!(
+        "{}{}{}{}{}{}{}",
+        left,
+        full_color,
+        full.repeat(filled),
+        empty_color,
+        empty.repeat(width - filled),
+        restore,
+        right
+    )
+}
+
+/// First percentage written in `text`, e.g. `42` in `"12G / 30G (42%)"`
+pub fn parse_percent(text: &str) -> Option<f64> {
+    text.match_indices('%').find_map(|(end, _)| {
+        let before = text[..end].trim_end_matches(|ch: char| ch.is_ascii_digit() || ch == '.');
+        text[before.len()..end].parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn display(options: &str) -> DisplayConfig {
+        parse_config(&format!(
+            "[display]\nascii_color = \"blue\"\nitems = []\n{}",
+            options
+        ))
+        .unwrap()
+        .display
+    }
+
+    #[test]
+    fn parse_percent_finds_the_first_percentage() {
+        assert_eq!(parse_percent("12.3 GiB / 31.2 GiB (39%)"), Some(39.0));
+        assert_eq!(parse_percent("87.5% (charging)"), Some(87.5));
+        // A lone `%` is skipped for the next percentage
+        assert_eq!(parse_percent("% of 20%"), Some(20.0));
+    }
+
+    #[test]
+    fn parse_percent_without_a_percent_sign() {
+        assert_eq!(parse_percent("12.3 GiB / 31.2 GiB"), None);
+        assert_eq!(parse_percent(""), None);
+    }
+
+    #[test]
+    fn render_fills_by_percent() {
+        let display = display("");
+        assert_eq!(render(39.0, None, &display, "", "", ""), "[■■■■□□□□□□]");
+        assert_eq!(render(45.0, None, &display, "", "", ""), "[■■■■■□□□□□]");
+        assert_eq!(render(0.0, Some(4), &display, "", "", ""), "[□□□□]");
+        assert_eq!(render(100.0, Some(4), &display, "", "", ""), "[■■■■]");
+    }
+
+    #[test]
+    fn render_clamps_out_of_range_percentages() {
+        let display = display("");
+        assert_eq!(render(150.0, Some(3), &display, "", "", ""), "[■■■]");
+        assert_eq!(render(-10.0, Some(3), &display, "", "", ""), "[□□□]");
+    }
+
+    #[test]
+    fn render_uses_configured_pieces_and_colors() {
+        let custom =
+            display("bar_width = 4\nbar_full = \"#\"\nbar_empty = \"-\"\nbar_border = \"|\"");
+        assert_eq!(render(50.0, None, &custom, "", "", ""), "|##--|");
+        assert_eq!(
+            render(50.0, None, &custom, "<full>", "<empty>", "<restore>"),
+            "|<full>##<empty>--<restore>|"
+        );
+        let borderless = display("bar_border = \"\"");
+        assert_eq!(render(50.0, Some(2), &borderless, "", "", ""), "■□");
+    }
+}
@@ -0,0 +1,539 @@
+//! Terminal color depth detection and downsampling of 24-bit colors
+
+use super::RenderOptions;
+use crate::config::DisplayConfig;
+use crate::utils::terminal;
+use crate::warnings::warn;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many colors the terminal can display, in increasing order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// Depth to render with: the one in `options` if set, or else following the
+/// `color` mode (`auto`, `always` or `never`, also set by `--color`) and
+/// `color_depth`.
+///
+/// In `auto` mode output is plain when `NO_COLOR` is set to a non-empty
+/// value or the output is not a terminal, so pipes and files get clean text.
+pub fn output_depth(display: &DisplayConfig, options: &RenderOptions) -> ColorDepth {
+    if let Some(depth) = options.color_depth {
+        return depth;
+    }
+    let mode = display.color.as_deref().unwrap_or("auto").to_lowercase();
+    match mode.as_str() {
+        "never" => ColorDepth::None,
+        // An explicit `color_depth` is kept as written, even `none`; a
+        // detected one gets at least the 16 basic colors
+        "always" => ColorDepth::configured(display.color_depth.as_deref())
+            .unwrap_or_else(|| ColorDepth::detect().max(ColorDepth::Ansi16)),
+        _ => {
+            if mode != "auto" {
+                warn(format!(
+                    "Unknown color mode '{}' (expected auto, always or never)",
+                    mode
+                ));
+            }
+            let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            if no_color || !(options.terminal && terminal::stdout_is_tty()) {
+                ColorDepth::None
+            } else {
+                ColorDepth::from_config(display.color_depth.as_deref())
+            }
+        }
+    }
+}
+
+/// Rows of palette blocks for a `type = "colors"` item.
+///
+/// `rows` lists the rows to draw, separated by spaces or commas: `normal`
+/// (colors 0-7), `bright` (8-15) and `256` (the 6x6x6 cube and gray ramp,
+/// one cell per color). Plain output has no colors to show, so nothing is
+/// drawn then; the 256-color ramp needs a terminal that supports it.
+pub fn palette_rows(
+    rows: &str,
+    symbol: Option<&str>,
+    width: Option<usize>,
+    depth: ColorDepth,
+) -> Vec<String> {
+    if depth == ColorDepth::None {
+        return Vec::new();
+    }
+
+    let symbol = symbol.unwrap_or("█");
+    let block = symbol.repeat(width.unwrap_or(3).max(1));
+    let basic_row = |first_code: u8| {
+        let mut line = String::new();
+        for code in first_code..first_code + 8 {
+            line.push_str(&format!("\x1b[{}m{}", code, block));
+        }
+        line.push_str("\x1b[0m");
+        line
+    };
+
+    let mut lines = Vec::new();
+    for row in rows
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|row| !row.is_empty())
+    {
+        match row.to_lowercase().as_str() {
+            "normal" => lines.push(basic_row(30)),
+            "bright" => lines.push(basic_row(90)),
+            "256" if depth >= ColorDepth::Ansi256 => {
+                let ramp_row = |codes: std::ops::Range<u16>| {
+                    let mut line: String = codes
+                        .map(|code| format!("\x1b[38;5;{}m{}", code, symbol))
+                        .collect();
+                    line.push_str("\x1b[0m");
+                    line
+                };
+                // Six rows of 36 cube colors, then the 24 grays
+                lines.extend((0..6).map(|row| ramp_row(16 + row * 36..16 + (row + 1) * 36)));
+                lines.push(ramp_row(232..256));
+            }
+            "256" => {}
+            other => warn(format!(
+                "Unknown palette row '{}' (expected normal, bright or 256)",
+                other
+            )),
+        }
+    }
+    lines
+}
+
+/// Levels of the 6x6x6 color cube in the xterm 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// xterm's default RGB values for the 16 basic colors (SGR 30-37, 90-97)
+pub const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// terminfo `max_colors` is the 14th numeric capability
+const TERMINFO_MAX_COLORS: usize = 13;
+
+impl ColorDepth {
+    pub fn from_name(name: &str) -> Option<ColorDepth> {
+        match name.to_lowercase().as_str() {
+            "truecolor" | "24bit" | "24-bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" | "8" => Some(ColorDepth::Ansi16),
+            "none" => Some(ColorDepth::None),
+            _ => None,
+        }
+    }
+
+    /// Resolve the `color_depth` option, detecting the depth for `auto` or unset
+    pub fn from_config(value: Option<&str>) -> ColorDepth {
+        ColorDepth::configured(value).unwrap_or_else(ColorDepth::detect)
+    }
+
+    /// The depth the `color_depth` option names, or `None` when it is unset,
+    /// `auto` or unknown and the depth has to be detected
+    fn configured(value: Option<&str>) -> Option<ColorDepth> {
+        let name = value.filter(|name| !name.eq_ignore_ascii_case("auto"))?;
+        let depth = ColorDepth::from_name(name);
+        if depth.is_none() {
+            warn(format!(
+                "Unknown color_depth '{}' (expected auto, truecolor, 256, 16 or none)",
+                name
+            ));
+        }
+        depth
+    }
+
+    /// Detect the depth from `COLORTERM`, `TERM` and the terminfo database
+    pub fn detect() -> ColorDepth {
+        if let Ok(colorterm) = env::var("COLORTERM") {
+            if matches!(colorterm.to_lowercase().as_str(), "truecolor" | "24bit") {
+                return ColorDepth::TrueColor;
+            }
+        }
+
+        // Without TERM there is nothing to go on; keep full colors
+        let Some(term) = env::var("TERM").ok().filter(|term| !term.is_empty()) else {
+            return ColorDepth::TrueColor;
+        };
+        if term == "dumb" {
+            return ColorDepth::None;
+        }
+        if term.ends_with("-direct") {
+            return ColorDepth::TrueColor;
+        }
+
+        if let Some(colors) = terminfo_max_colors(&term) {
+            return match colors {
+                0x0100_0000.. => ColorDepth::TrueColor,
+                256.. => ColorDepth::Ansi256,
+                8.. => ColorDepth::Ansi16,
+                _ => ColorDepth::None,
+            };
+        }
+
+        if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// SGR parameters for an RGB color on layer 38 (foreground) or 48 (background)
+    pub fn rgb_sgr(self, layer: u8, r: u8, g: u8, b: u8) -> Option<String> {
+        match self {
+            ColorDepth::TrueColor => Some(format!("{};2;{};{};{}", layer, r, g, b)),
+            ColorDepth::Ansi256 => Some(format!("{};5;{}", layer, nearest_256(r, g, b))),
+            ColorDepth::Ansi16 => {
+                let index = nearest_16(r, g, b);
+                let base = if layer == 38 { 30 } else { 40 };
+                let code = if index < 8 {
+                    base + index
+                } else {
+                    base + 60 + index - 8
+                };
+                Some(code.to_string())
+            }
+            ColorDepth::None => None,
+        }
+    }
+
+    /// Rewrite every 24-bit color in the SGR sequences of `text` for this
+    /// depth; with `None`, SGR sequences are removed altogether.
+    pub fn downsample(self, text: &str) -> String {
+        if self == ColorDepth::TrueColor || !text.contains("\x1b[") {
+            return text.to_string();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("\x1b[") {
+            output.push_str(&rest[..start]);
+            let body = &rest[start + 2..];
+            let Some(end) = body.find(|ch: char| !(ch.is_ascii_digit() || ch == ';')) else {
+                output.push_str(&rest[start..]);
+                return output;
+            };
+            let final_len = body[end..].chars().next().map_or(1, char::len_utf8);
+            if body.as_bytes()[end] != b'm' {
+                // Not SGR: keep as-is
+                output.push_str(&rest[start..start + 2 + end + final_len]);
+            } else if self != ColorDepth::None {
+                output.push_str("\x1b[");
+                output.push_str(&self.downsample_params(&body[..end]));
+                output.push('m');
+            }
+            rest = &body[end + final_len..];
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn downsample_params(self, params: &str) -> String {
+        let values: Vec<&str> = params.split(';').collect();
+        let mut output: Vec<String> = Vec::with_capacity(values.len());
+        let mut index = 0;
+        while index < values.len() {
+            let value = values[index];
+            if (value == "38" || value == "48") && values.get(index + 1) == Some(&"2") {
+                let channel = |offset: usize| {
+                    values
+                        .get(index + offset)
+                        .and_then(|channel| channel.parse::<u8>().ok())
+                        .unwrap_or(0)
+                };
+                let layer = if value == "38" { 38 } else { 48 };
+                if let Some(params) = self.rgb_sgr(layer, channel(2), channel(3), channel(4)) {
+                    output.push(params);
+                }
+                index += 5;
+            } else {
+                output.push(value.to_string());
+                index += 1;
+            }
+        }
+        output.join(";")
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+/// Closest entry of the xterm palette above 15 (color cube or gray ramp)
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |value: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (value as i32 - **level as i32).abs())
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    // Gray ramp 232..=255 covers 8, 18, ..., 238
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23);
+    let gray_value = (8 + gray_step * 10) as u8;
+    let gray = (gray_value, gray_value, gray_value);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_step as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> u8 {
+    ANSI16_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, color)| distance((r, g, b), **color))
+        .map(|(index, _)| index as u8)
+        .unwrap_or(7)
+}
+
+/// Read `max_colors` from the compiled terminfo entry for `term`
+fn terminfo_max_colors(term: &str) -> Option<u32> {
+    let first = term.chars().next()?;
+    let data = terminfo_dirs().into_iter().find_map(|dir| {
+        fs::read(dir.join(first.to_string()).join(term))
+            .or_else(|_| fs::read(dir.join(format!("{:x}", first as u32)).join(term)))
+            .ok()
+    })?;
+    parse_max_colors(&data)
+}
+
+/// `max_colors` from a compiled terminfo entry, in either number format
+fn parse_max_colors(data: &[u8]) -> Option<u32> {
+    let short = |offset: usize| -> Option<i32> {
+        let bytes = data.get(offset..offset + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]) as i32)
+    };
+    // The legacy format stores numbers as 16-bit values, the extended one as 32-bit
+    let number_size = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names_size = short(2)? as usize;
+    let bools_count = short(4)? as usize;
+    let numbers_count = short(6)? as usize;
+    if numbers_count <= TERMINFO_MAX_COLORS {
+        return None;
+    }
+
+    // Numbers are aligned to an even offset
+    let mut numbers_start = 12 + names_size + bools_count;
+    numbers_start += numbers_start % 2;
+    let offset = numbers_start + TERMINFO_MAX_COLORS * number_size;
+    let bytes = data.get(offset..offset + number_size)?;
+    let value = if number_size == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i64
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64
+    };
+    u32::try_from(value).ok()
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        dirs.extend(
+            list.split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+    dirs.extend(
+        ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"]
+            .iter()
+            .map(PathBuf::from),
+    );
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn display(options: &str) -> DisplayConfig {
+        parse_config(&format!(
+            "[display]\nascii_color = \"blue\"\nitems = []\n{}",
+            options
+        ))
+        .unwrap()
+        .display
+    }
+
+    #[test]
+    fn always_keeps_an_explicit_depth() {
+        let options = RenderOptions::default();
+        for (depth, expected) in [
+            ("none", ColorDepth::None),
+            ("16", ColorDepth::Ansi16),
+            ("256", ColorDepth::Ansi256),
+        ] {
+            let display = display(&format!("color = \"always\"\ncolor_depth = \"{}\"", depth));
+            assert_eq!(output_depth(&display, &options), expected);
+        }
+        let display = display("color = \"never\"\ncolor_depth = \"256\"");
+        assert_eq!(output_depth(&display, &options), ColorDepth::None);
+    }
+
+    #[test]
+    fn nearest_256_picks_cube_or_gray() {
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(255, 0, 0), 196);
+        assert_eq!(nearest_256(255, 255, 255), 231);
+        assert_eq!(nearest_256(95, 135, 175), 67);
+        assert_eq!(nearest_256(128, 128, 128), 244);
+        assert_eq!(nearest_256(8, 8, 8), 232);
+    }
+
+    #[test]
+    fn nearest_16_uses_the_xterm_palette() {
+        assert_eq!(nearest_16(205, 0, 0), 1);
+        assert_eq!(nearest_16(255, 10, 10), 9);
+        assert_eq!(nearest_16(0, 0, 230), 4);
+        assert_eq!(nearest_16(250, 250, 250), 15);
+        assert_eq!(nearest_16(120, 120, 120), 8);
+    }
+
+    #[test]
+    fn rgb_sgr_per_depth() {
+        assert_eq!(
+            ColorDepth::TrueColor.rgb_sgr(38, 1, 2, 3).as_deref(),
+            Some("38;2;1;2;3")
+        );
+        assert_eq!(
+            ColorDepth::Ansi256.rgb_sgr(48, 255, 0, 0).as_deref(),
+            Some("48;5;196")
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.rgb_sgr(38, 205, 0, 0).as_deref(),
+            Some("31")
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.rgb_sgr(48, 255, 255, 255).as_deref(),
+            Some("107")
+        );
+        assert_eq!(ColorDepth::None.rgb_sgr(38, 1, 2, 3), None);
+    }
+
+    #[test]
+    fn downsample_rewrites_only_truecolor_parameters() {
+        let text = "\x1b[1;38;2;255;0;0mred\x1b[48;2;0;0;0;4m\x1b[2Kend\x1b[0m";
+        assert_eq!(ColorDepth::TrueColor.downsample(text), text);
+        assert_eq!(
+            ColorDepth::Ansi256.downsample(text),
+            "\x1b[1;38;5;196mred\x1b[48;5;16;4m\x1b[2Kend\x1b[0m"
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.downsample(text),
+            "\x1b[1;91mred\x1b[40;4m\x1b[2Kend\x1b[0m"
+        );
+        assert_eq!(ColorDepth::None.downsample(text), "red\x1b[2Kend");
+    }
+
+    /// A compiled terminfo entry with `numbers`, stored 2 or 4 bytes wide
+    fn terminfo(magic: i16, number_size: usize, numbers: &[i32]) -> Vec<u8> {
+        let names = b"x|test\0";
+        let bools = [1u8, 0, 1];
+        let mut data = Vec::new();
+        for header in [
+            magic,
+            names.len() as i16,
+            bools.len() as i16,
+            numbers.len() as i16,
+            0,
+            0,
+        ] {
+            data.extend(header.to_le_bytes());
+        }
+        data.extend(names);
+        data.extend(bools);
+        // 12 + 7 + 3 bytes so far, which puts the numbers at an even offset
+        assert_eq!(data.len() % 2, 0);
+        for &number in numbers {
+            data.extend(&number.to_le_bytes()[..number_size]);
+        }
+        data
+    }
+
+    #[test]
+    fn terminfo_legacy_numbers() {
+        let mut numbers = vec![-1; 14];
+        numbers[13] = 256;
+        assert_eq!(parse_max_colors(&terminfo(0o432, 2, &numbers)), Some(256));
+        numbers[13] = -1;
+        assert_eq!(parse_max_colors(&terminfo(0o432, 2, &numbers)), None);
+    }
+
+    #[test]
+    fn terminfo_extended_numbers() {
+        let mut numbers = vec![0; 15];
+        numbers[13] = 0x0100_0000;
+        assert_eq!(
+            parse_max_colors(&terminfo(0o1036, 4, &numbers)),
+            Some(0x0100_0000)
+        );
+    }
+
+    #[test]
+    fn terminfo_aligns_numbers_to_an_even_offset() {
+        // An odd-sized names section is followed by a padding byte
+        let mut data = Vec::new();
+        for header in [0o432i16, 3, 0, 14, 0, 0] {
+            data.extend(header.to_le_bytes());
+        }
+        data.extend(b"xy\0");
+        data.push(0);
+        for index in 0..14i16 {
+            data.extend((if index == 13 { 88i16 } else { 0 }).to_le_bytes());
+        }
+        assert_eq!(parse_max_colors(&data), Some(88));
+    }
+
+    #[test]
+    fn terminfo_rejects_bad_entries() {
+        let numbers = vec![8; 14];
+        assert_eq!(parse_max_colors(&terminfo(0o433, 2, &numbers)), None);
+        assert_eq!(parse_max_colors(&terminfo(0o432, 2, &numbers[..13])), None);
+        let truncated = terminfo(0o432, 2, &numbers);
+        assert_eq!(parse_max_colors(&truncated[..truncated.len() - 1]), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! Text for collected values, and the numbers behind them for thresholds
+//! and usage bars
+
+use crate::data::{
+    BatteryInfo, ChargingState, DiskInfo, Gpu, GpuKind, MemoryInfo, UserInfo, Value,
+};
+use std::time::{Duration, SystemTime};
+
+/// How `value` is shown
+pub fn text(value: &Value) -> String {
+    match value {
+        Value::Text(text) => text.clone(),
+        Value::Count(count) => count.to_string(),
+        Value::Memory(memory) => memory_usage(memory),
+        Value::Disk(disk) => disk_usage(disk),
+        Value::Seconds(seconds) => uptime(*seconds),
+        Value::Timestamp(time) => age(*time),
+        Value::Celsius(celsius) => format!("{:.1}°C", celsius),
+        Value::Battery(battery) => battery_charge(battery),
+        Value::Gpu(gpu) => gpu_name(gpu),
+        Value::User(user) => user_at_host(user),
+        Value::Missing(what) => what.to_string(),
+    }
+}
+
+/// Number `thresholds` compare: percentages for usage and battery values,
+/// °C, seconds or counts for the others
+pub fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Count(count) => Some(*count as f64),
+        Value::Seconds(seconds) => Some(*seconds as f64),
+        Value::Celsius(celsius) => Some(*celsius),
+        _ => percent(value),
+    }
+}
+
+/// Percentage a usage bar shows, for values that have one
+pub fn percent(value: &Value) -> Option<f64> {
+    match value {
+        Value::Memory(memory) => memory.percent(),
+        Value::Disk(disk) => disk.percent(),
+        Value::Battery(battery) => Some(battery.percent),
+        _ => None,
+    }
+}
+
+/// `used / total` in GiB, e.g. `5.21 GB / 30.61 GB`
+fn memory_usage(memory: &MemoryInfo) -> String {
+    let gib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    format!(
+        "{:.2} GB / {:.2} GB",
+        gib(memory.used_bytes),
+        gib(memory.total_bytes)
+    )
+}
+
+/// `used / total (percent)` in decimal units, e.g. `186.1G / 270.6G (68%)`
+fn disk_usage(disk: &DiskInfo) -> String {
+    format!(
+        "{} / {} ({}%)",
+        size(disk.used_bytes),
+        size(disk.total_bytes),
+        disk.percent().unwrap_or(0.0) as u64
+    )
+}
+
+/// Byte count with a decimal unit, like `df -H`
+fn size(bytes: u64) -> String {
+    if bytes >= 1_000_000_000_000 {
+        format!("{:.1}T", bytes as f64 / 1_000_000_000_000.0)
+    } else if bytes >= 1_000_000_000 {
+        format!("{:.1}G", bytes as f64 / 1_000_000_000.0)
+    } else if bytes >= 1_000_000 {
+        format!("{:.1}M", bytes as f64 / 1_000_000.0)
+    } else {
+        format!("{}K", bytes / 1024)
+    }
+}
+
+/// Hours and minutes, e.g. `3h 02m`
+fn uptime(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Whole days since `time`, e.g. `20 days`
+fn age(time: SystemTime) -> String {
+    let days = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+        / 86400;
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+/// Charge with a status icon, e.g. `85% ⚡`
+fn battery_charge(battery: &BatteryInfo) -> String {
+    let icon = match battery.state {
+        ChargingState::Charging => "⚡",
+        ChargingState::NotCharging => "🔌",
+        ChargingState::Discharging | ChargingState::Full | ChargingState::Unknown => "🔋",
+    };
+    format!("{}% {}", battery.percent, icon)
+}
+
+/// Model name tagged with the kind, e.g. `Intel UHD Graphics 620 [Integrated]`
+fn gpu_name(gpu: &Gpu) -> String {
+    let kind = match gpu.kind {
+        GpuKind::Discrete => "Discrete",
+        GpuKind::Integrated => "Integrated",
+    };
+    format!("{} [{}]", gpu.name, kind)
+}
+
+/// `user@host`
+fn user_at_host(user: &UserInfo) -> String {
+    format!("{}@{}", user.username, user.hostname)
+}
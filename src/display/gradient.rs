@@ -0,0 +1,161 @@
+//! Horizontal and vertical color gradients for logos and text lines
+
+use super::color::ColorDepth;
+use super::style::{color_rgb, Style};
+use crate::config::ColorSpec;
+use crate::warnings::warn;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthChar;
+
+pub struct Gradient {
+    stops: Vec<(u8, u8, u8)>,
+    vertical: bool,
+}
+
+impl Gradient {
+    /// The gradient described by `spec`, or `None` for plain color specs.
+    /// Stops may be `[colors]` keys; stops without an RGB value are skipped.
+    pub fn from_spec(spec: &ColorSpec, colors: &HashMap<String, ColorSpec>) -> Option<Gradient> {
+        let spec = match spec {
+            ColorSpec::Spec(key) => colors.get(key.trim())?,
+            spec => spec,
+        };
+        let ColorSpec::Gradient {
+            gradient,
+            direction,
+        } = spec
+        else {
+            return None;
+        };
+
+        let stops: Vec<(u8, u8, u8)> = gradient
+            .iter()
+            .filter_map(|stop| {
+                let style = Style::resolve(&ColorSpec::Spec(stop.clone()), colors);
+                color_rgb(style.fg.as_deref()?)
+            })
+            .collect();
+        if stops.is_empty() {
+            warn("Gradient has no usable colors, ignoring it");
+            return None;
+        }
+
+        let vertical = match direction.as_deref().unwrap_or("horizontal") {
+            "horizontal" => false,
+            "vertical" => true,
+            other => {
+                warn(format!(
+                    "Unknown gradient direction '{}' (expected horizontal or vertical), using horizontal",
+                    other
+                ));
+                false
+            }
+        };
+        Some(Gradient { stops, vertical })
+    }
+
+    /// Color `position` (0.0 to 1.0) along the gradient
+    fn at(&self, position: f64) -> (u8, u8, u8) {
+        if self.stops.len() == 1 {
+            return self.stops[0];
+        }
+        let scaled = position.clamp(0.0, 1.0) * (self.stops.len() - 1) as f64;
+        let index = (scaled as usize).min(self.stops.len() - 2);
+        let fraction = scaled - index as f64;
+        let (from, to) = (self.stops[index], self.stops[index + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+        (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+
+    /// Color a block of lines such as a logo. Horizontal gradients span the
+    /// widest line so columns line up; vertical ones run from the first line
+    /// to the last. Lines that carry their own escape sequences are kept.
+    pub fn paint(&self, lines: &[String], depth: ColorDepth) -> Vec<String> {
+        let sgr = |rgb| sgr(rgb, depth);
+
+        if self.vertical {
+            return lines
+                .iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    if line.contains('\x1b') {
+                        return line.clone();
+                    }
+                    format!("{}{}", sgr(self.at(fraction(row, lines.len()))), line)
+                })
+                .collect();
+        }
+
+        let width = lines
+            .iter()
+            .map(|line| unicode_width::UnicodeWidthStr::width(line.as_str()))
+            .max()
+            .unwrap_or(0);
+        lines
+            .iter()
+            .map(|line| {
+                if line.contains('\x1b') {
+                    return line.clone();
+                }
+                let mut painted = String::with_capacity(line.len() * 4);
+                let mut column = 0;
+                let mut current = String::new();
+                for ch in line.chars() {
+                    let ch_width = ch.width().unwrap_or(0);
+                    if !ch.is_whitespace() {
+                        // Sample the middle of wide characters
+                        let center = column + ch_width.saturating_sub(1) / 2;
+                        let code = sgr(self.at(fraction(center, width)));
+                        // Neighbours often map to the same code at low depths
+                        if code != current {
+                            painted.push_str(&code);
+                            current = code;
+                        }
+                    }
+                    painted.push(ch);
+                    column += ch_width;
+                }
+                painted
+            })
+            .collect()
+    }
+    /// Codes for the left and right edges of each of the `rows` lines of a
+    /// frame `width` columns wide, matching what `paint` gives its top and
+    /// bottom lines
+    pub fn frame_sides(
+        &self,
+        width: usize,
+        rows: usize,
+        depth: ColorDepth,
+    ) -> Vec<(String, String)> {
+        (0..rows)
+            .map(|row| {
+                if self.vertical {
+                    let code = sgr(self.at(fraction(row, rows)), depth);
+                    (code.clone(), code)
+                } else {
+                    (
+                        sgr(self.at(0.0), depth),
+                        sgr(self.at(fraction(width.saturating_sub(1), width)), depth),
+                    )
+                }
+            })
+            .collect()
+    }
+}
+
+fn sgr((r, g, b): (u8, u8, u8), depth: ColorDepth) -> String {
+    depth
+        .rgb_sgr(38, r, g, b)
+        .map(|params| format!("\x1b[{}m", params))
+        .unwrap_or_default()
+}
+
+/// Position of `index` among `count` evenly spaced steps, from 0.0 to 1.0
+fn fraction(index: usize, count: usize) -> f64 {
+    if count > 1 {
+        index as f64 / (count - 1) as f64
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,44 @@
+//! Unicode half-block rendering (two pixels per cell using `▀`/`▄`)
+
+use image::{imageops::FilterType, DynamicImage, Rgba};
+
+const ALPHA_CUTOFF: u8 = 128;
+
+/// Render `image` into `columns` x `rows` cells of colored half blocks
+pub fn render(image: &DynamicImage, columns: usize, rows: usize) -> Vec<String> {
+    let pixels = image
+        .resize_exact(columns as u32, (rows * 2) as u32, FilterType::Triangle)
+        .to_rgba8();
+
+    let mut lines = Vec::with_capacity(rows);
+    for row in 0..rows as u32 {
+        let mut line = String::with_capacity(columns * 24);
+        for x in 0..columns as u32 {
+            let top = visible(pixels.get_pixel(x, row * 2));
+            let bottom = visible(pixels.get_pixel(x, row * 2 + 1));
+
+            match (top, bottom) {
+                (Some(top), Some(bottom)) => line.push_str(&format!(
+                    "\x1b[38;2;{};{};{};48;2;{};{};{}m▀",
+                    top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+                )),
+                (Some(top), None) => {
+                    line.push_str(&format!("\x1b[0;38;2;{};{};{}m▀", top[0], top[1], top[2]))
+                }
+                (None, Some(bottom)) => line.push_str(&format!(
+                    "\x1b[0;38;2;{};{};{}m▄",
+                    bottom[0], bottom[1], bottom[2]
+                )),
+                (None, None) => line.push_str("\x1b[0m "),
+            }
+        }
+        line.push_str("\x1b[0m");
+        lines.push(line);
+    }
+
+    lines
+}
+
+fn visible(pixel: &Rgba<u8>) -> Option<[u8; 3]> {
+    (pixel[3] >= ALPHA_CUTOFF).then_some([pixel[0], pixel[1], pixel[2]])
+}
@@ -0,0 +1,317 @@
+//! Kitty graphics protocol transmission
+
+use super::multiplexer::Multiplexer;
+use super::prepare::Animation;
+use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
+use base64::Engine;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::{env, fmt::Write, fs};
+
+const CHUNK: usize = 4096;
+/// Unicode placeholder character for virtual placements (`U=1`)
+const PLACEHOLDER: char = '\u{10EEEE}';
+/// Combining marks encoding row/column numbers of placeholder cells, in the
+/// order defined by kitty's `rowcolumn-diacritics.txt`.
+const DIACRITICS: [u32; 85] = [
+    0x0305, 0x030D, 0x030E, 0x0310, 0x0312, 0x033D, 0x033E, 0x033F, 0x0346, 0x034A, 0x034B, 0x034C,
+    0x0350, 0x0351, 0x0352, 0x0357, 0x035B, 0x0363, 0x0364, 0x0365, 0x0366, 0x0367, 0x0368, 0x0369,
+    0x036A, 0x036B, 0x036C, 0x036D, 0x036E, 0x036F, 0x0483, 0x0484, 0x0485, 0x0486, 0x0487, 0x0592,
+    0x0593, 0x0594, 0x0595, 0x0597, 0x0598, 0x0599, 0x059C, 0x059D, 0x059E, 0x059F, 0x05A0, 0x05A1,
+    0x05A8, 0x05A9, 0x05AB, 0x05AC, 0x05AF, 0x05C4, 0x0610, 0x0611, 0x0612, 0x0613, 0x0614, 0x0615,
+    0x0616, 0x0617, 0x0657, 0x0658, 0x0659, 0x065A, 0x065B, 0x065D, 0x065E, 0x06D6, 0x06D7, 0x06D8,
+    0x06D9, 0x06DA, 0x06DB, 0x06DC, 0x06DF, 0x06E0, 0x06E1, 0x06E2, 0x06E4, 0x06E7, 0x06E8, 0x06EB,
+    0x06EC,
+];
+
+/// How image data reaches the terminal (the `t` key)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transmission {
+    /// Base64 payload inside the escape sequences (`t=d`), works everywhere
+    Direct,
+    /// Path to a PNG the terminal reads itself (`t=f`)
+    File,
+    /// Temporary file the terminal deletes after reading (`t=t`)
+    TempFile,
+    /// POSIX shared memory object the terminal unlinks after reading (`t=s`)
+    SharedMemory,
+}
+
+impl Transmission {
+    pub fn from_name(name: &str) -> Option<Transmission> {
+        match name.to_lowercase().as_str() {
+            "direct" => Some(Transmission::Direct),
+            "file" => Some(Transmission::File),
+            "tempfile" | "temp_file" => Some(Transmission::TempFile),
+            "shm" | "shared_memory" => Some(Transmission::SharedMemory),
+            _ => None,
+        }
+    }
+}
+
+/// One image upload: its stable id and how (or whether) to send the pixels
+pub struct Upload<'a> {
+    /// PNG-encoded pixels at their final size
+    pub png: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub id: u32,
+    /// The terminal still holds this id from an earlier run; only place it
+    pub already_loaded: bool,
+    pub transmission: Transmission,
+    /// File on disk holding exactly `png`, usable for `t=f`
+    pub png_path: Option<&'a Path>,
+    /// Further frames, sent with the animation protocol after `png`
+    pub animation: Option<&'a Animation>,
+}
+
+/// Transmit and display the image at the cursor
+pub fn render(
+    upload: &Upload,
+    horizontal_offset: i32,
+    vertical_offset: i32,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<(), String> {
+    let placement = format!(
+        "s={},v={},x={},y={}",
+        upload.width, upload.height, horizontal_offset, vertical_offset
+    );
+
+    send(upload, &placement, multiplexer, output)?;
+    output.push('\n');
+    Ok(())
+}
+
+/// Transmit the image as a virtual placement and return the placeholder
+/// lines that display it.
+///
+/// The placeholders are ordinary text, so the image scrolls with the output
+/// and survives multiplexer pane redraws.
+pub fn render_placeholders(
+    upload: &Upload,
+    columns: usize,
+    rows: usize,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<Vec<String>, String> {
+    if rows > DIACRITICS.len() || columns > DIACRITICS.len() {
+        return Err(format!(
+            "Image is too large for Unicode placeholders ({}x{} cells, max {})",
+            columns,
+            rows,
+            DIACRITICS.len()
+        ));
+    }
+
+    let placement = format!("U=1,c={},r={}", columns, rows);
+    send(upload, &placement, multiplexer, output)?;
+
+    // The image id is carried in the 24-bit foreground color of the cells
+    let image_id = upload.id;
+    let color = format!(
+        "\x1b[38;2;{};{};{}m",
+        (image_id >> 16) & 0xFF,
+        (image_id >> 8) & 0xFF,
+        image_id & 0xFF
+    );
+
+    let lines = (0..rows)
+        .map(|row| {
+            let mut line = String::with_capacity(color.len() + columns * 4 + 16);
+            line.push_str(&color);
+            // Only the first cell needs row/column marks; the rest are inferred
+            line.push(PLACEHOLDER);
+            line.push(char::from_u32(DIACRITICS[row]).unwrap_or(PLACEHOLDER));
+            line.push(char::from_u32(DIACRITICS[0]).unwrap_or(PLACEHOLDER));
+            line.extend(std::iter::repeat_n(PLACEHOLDER, columns.saturating_sub(1)));
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect();
+
+    Ok(lines)
+}
+
+/// Emit the upload (or just a placement of an already loaded id).
+///
+/// Every command carries `q=2` so the terminal's replies do not end up as
+/// input in the shell after swiftfetch exits.
+fn send(
+    upload: &Upload,
+    placement: &str,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<(), String> {
+    if upload.already_loaded {
+        let sequence = format!("\x1b_Ga=p,i={},q=2,{}\x1b\\", upload.id, placement);
+        output.push_str(&multiplexer.wrap(&sequence));
+    } else {
+        let control = format!("a=T,f=100,i={},q=2,{}", upload.id, placement);
+        transmit(
+            upload,
+            &control,
+            upload.png,
+            upload.png_path,
+            0,
+            multiplexer,
+            output,
+        )?;
+    }
+
+    if let Some(animation) = upload.animation {
+        if !upload.already_loaded {
+            send_frames(upload, animation, multiplexer, output)?;
+        }
+        // Loop forever (`v=1`); a reused image may have been stopped meanwhile
+        let start = format!("\x1b_Ga=a,i={},s=3,v=1,q=2\x1b\\", upload.id);
+        output.push_str(&multiplexer.wrap(&start));
+    }
+    Ok(())
+}
+
+/// Append the remaining frames to the uploaded image (`a=f`) and set the
+/// display time of the first one, which `a=T` cannot carry.
+fn send_frames(
+    upload: &Upload,
+    animation: &Animation,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<(), String> {
+    for (index, frame) in animation.frames.iter().enumerate() {
+        let control = format!("a=f,f=100,i={},z={},q=2", upload.id, frame.delay_ms);
+        transmit(
+            upload,
+            &control,
+            &frame.png,
+            frame.png_path.as_deref(),
+            index + 1,
+            multiplexer,
+            output,
+        )?;
+    }
+
+    let gap = format!(
+        "\x1b_Ga=a,i={},r=1,z={},q=2\x1b\\",
+        upload.id, animation.first_delay_ms
+    );
+    output.push_str(&multiplexer.wrap(&gap));
+    Ok(())
+}
+
+/// Send one PNG with `control`, using the configured transmission medium
+fn transmit(
+    upload: &Upload,
+    control: &str,
+    png: &[u8],
+    png_path: Option<&Path>,
+    frame: usize,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<(), String> {
+    let name = format!(
+        "swiftfetch-tty-graphics-protocol-{}-{}-{}",
+        std::process::id(),
+        upload.id,
+        frame
+    );
+    let medium = match upload.transmission {
+        Transmission::Direct => None,
+        Transmission::File => match png_path {
+            Some(path) => Some(("t=f".to_string(), path.to_string_lossy().into_owned())),
+            None => write_temp_png(png, &name).map(|path| ("t=t".to_string(), path)),
+        },
+        Transmission::TempFile => write_temp_png(png, &name).map(|path| ("t=t".to_string(), path)),
+        Transmission::SharedMemory => write_shared_memory(png, &name),
+    };
+
+    match medium {
+        Some((keys, location)) => {
+            let sequence = format!(
+                "\x1b_G{},{};{}\x1b\\",
+                control,
+                keys,
+                BASE64_ENGINE.encode(location.as_bytes())
+            );
+            output.push_str(&multiplexer.wrap(&sequence));
+            Ok(())
+        }
+        None => transmit_png(png, control, multiplexer, output),
+    }
+}
+
+/// Kitty only deletes temp files whose path contains `tty-graphics-protocol`
+fn write_temp_png(png: &[u8], name: &str) -> Option<String> {
+    let path: PathBuf = env::temp_dir().join(format!("{}.png", name));
+    fs::write(&path, png).ok()?;
+    Some(path.to_string_lossy().into_owned())
+}
+
+fn write_shared_memory(png_bytes: &[u8], name: &str) -> Option<(String, String)> {
+    let name = format!("/{}", name);
+    let c_name = CString::new(name.clone()).ok()?;
+
+    unsafe {
+        let fd = libc::shm_open(
+            c_name.as_ptr(),
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+            0o600,
+        );
+        if fd < 0 {
+            return None;
+        }
+
+        let mut remaining = png_bytes;
+        while !remaining.is_empty() {
+            let written = libc::write(
+                fd,
+                remaining.as_ptr() as *const libc::c_void,
+                remaining.len(),
+            );
+            if written <= 0 {
+                libc::close(fd);
+                libc::shm_unlink(c_name.as_ptr());
+                return None;
+            }
+            remaining = &remaining[written as usize..];
+        }
+        libc::close(fd);
+    }
+
+    // The terminal unlinks the object once it has read it
+    Some((format!("t=s,S={}", png_bytes.len()), name))
+}
+
+fn transmit_png(
+    png_bytes: &[u8],
+    control: &str,
+    multiplexer: Multiplexer,
+    output: &mut String,
+) -> Result<(), String> {
+    let encoded = BASE64_ENGINE.encode(png_bytes);
+    let mut start = 0usize;
+    let mut sequence = String::with_capacity(CHUNK + 64);
+
+    while start < encoded.len() {
+        let end = (start + CHUNK).min(encoded.len());
+        let chunk = &encoded[start..end];
+        let more_flag = if end < encoded.len() { 1 } else { 0 };
+
+        sequence.clear();
+        // Only the first chunk carries the full control data
+        if start == 0 {
+            write!(sequence, "\x1b_G{},m={};", control, more_flag)
+        } else {
+            write!(sequence, "\x1b_Gm={};", more_flag)
+        }
+        .map_err(|_| "Failed to write Kitty image escape sequence".to_string())?;
+
+        sequence.push_str(chunk);
+        sequence.push_str("\x1b\\");
+        output.push_str(&multiplexer.wrap(&sequence));
+
+        start = end;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,361 @@
+//! Picture logos rendered through terminal graphics protocols
+//!
+//! The backend is negotiated at runtime: the terminal is probed once and the
+//! first entry of `image_protocols` it supports wins. Backends that fail to
+//! render fall through to the next entry.
+
+mod halfblock;
+mod kitty;
+mod multiplexer;
+mod prepare;
+mod probe;
+mod sixel;
+
+pub use self::prepare::open_image;
+
+use self::kitty::{Transmission, Upload};
+use self::multiplexer::Multiplexer;
+use self::prepare::PreparedImage;
+use crate::config::DisplayConfig;
+use crate::utils::cache::CacheKey;
+use crate::utils::terminal;
+use crate::warnings::warn;
+use std::{env, path::Path};
+
+const DEFAULT_CHAR_WIDTH: f32 = 11.5;
+const DEFAULT_CHAR_HEIGHT: f32 = 18.0;
+const DEFAULT_GAP_COLUMNS: usize = 1;
+const DEFAULT_PROTOCOLS: [&str; 4] = ["kitty", "sixel", "halfblock", "ascii"];
+
+/// Left-column artwork produced by a graphics backend
+pub struct Artwork {
+    /// Escape sequences that draw the image at the cursor (may be empty)
+    pub preamble: String,
+    /// Lines occupying the left column (blank padding for drawn images)
+    pub lines: Vec<String>,
+    /// The line colors carry data (kitty placeholders) and must be kept exact
+    pub exact_colors: bool,
+    /// The image is drawn by `preamble` and `lines` only keep its column
+    /// clear, so the caller can extend them to the height of the info
+    pub padding: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Backend {
+    Kitty,
+    Sixel,
+    HalfBlock,
+    Ascii,
+}
+
+impl Backend {
+    fn from_name(name: &str) -> Option<Backend> {
+        match name.to_lowercase().as_str() {
+            "kitty" => Some(Backend::Kitty),
+            "sixel" => Some(Backend::Sixel),
+            "halfblock" | "half_block" | "blocks" => Some(Backend::HalfBlock),
+            "ascii" => Some(Backend::Ascii),
+            _ => None,
+        }
+    }
+}
+
+/// Terminal-space geometry of the (already resized) image
+struct Layout {
+    columns: usize,
+    rows: usize,
+    pad_columns: usize,
+    pad_rows: usize,
+    offset_columns: i32,
+    vertical_offset_rows: i32,
+}
+
+/// Per-run state shared by the backends
+struct Session<'a> {
+    display: &'a DisplayConfig,
+    multiplexer: Multiplexer,
+    capabilities: probe::Capabilities,
+    transmission: Transmission,
+    /// Stable kitty image id derived from the source file and target size
+    image_id: u32,
+}
+
+/// Render `path` with the best backend available.
+///
+/// Returns `None` when the negotiated choice is plain ASCII or every backend
+/// failed, in which case the caller falls back to ASCII art.
+pub fn render_artwork(display: &DisplayConfig, path: &str) -> Option<Artwork> {
+    let order: Vec<Backend> = match display.image_protocols.as_ref() {
+        Some(names) => names
+            .iter()
+            .filter_map(|name| {
+                let backend = Backend::from_name(name);
+                if backend.is_none() {
+                    warn(format!("Ignoring unknown image protocol '{}'", name));
+                }
+                backend
+            })
+            .collect(),
+        None => DEFAULT_PROTOCOLS
+            .iter()
+            .filter_map(|name| Backend::from_name(name))
+            .collect(),
+    };
+
+    let needs_probe = order
+        .iter()
+        .any(|backend| matches!(backend, Backend::Kitty | Backend::Sixel));
+    let multiplexer = Multiplexer::detect();
+    let configured_transmission = display.image_transmission.as_deref().and_then(|name| {
+        if name.eq_ignore_ascii_case("auto") {
+            return None;
+        }
+        let transmission = Transmission::from_name(name);
+        if transmission.is_none() {
+            warn(format!("Ignoring unknown image transmission '{}'", name));
+        }
+        transmission
+    });
+    // Files and shared memory only make sense when the terminal runs on this machine
+    let local_session = !is_remote_session() && !multiplexer.is_active();
+
+    let image_id = kitty_image_id(path, display);
+    let capabilities = if needs_probe {
+        probe::detect_capabilities(&probe::ProbeOptions {
+            multiplexer,
+            kitty_image_id: image_id,
+            kitty_temp_files: local_session && configured_transmission.is_none(),
+        })
+    } else {
+        probe::Capabilities::default()
+    };
+
+    let transmission = configured_transmission.unwrap_or(if capabilities.kitty_temp_files {
+        Transmission::File
+    } else {
+        Transmission::Direct
+    });
+    let session = Session {
+        display,
+        multiplexer,
+        capabilities,
+        transmission,
+        image_id: image_id.unwrap_or(1),
+    };
+
+    let mut image: Option<PreparedImage> = None;
+    for backend in order {
+        let supported = match backend {
+            Backend::Kitty => session.capabilities.kitty,
+            Backend::Sixel => session.capabilities.sixel,
+            Backend::HalfBlock | Backend::Ascii => true,
+        };
+        if !supported {
+            continue;
+        }
+        if backend == Backend::Ascii {
+            return None;
+        }
+
+        if image.is_none() {
+            match prepare::prepare(path, display) {
+                Ok(loaded) => image = Some(loaded),
+                Err(err) => {
+                    warn(format!(
+                        "Image rendering failed (falling back to ASCII): {}",
+                        err
+                    ));
+                    return None;
+                }
+            }
+        }
+
+        match render_with(backend, image.as_ref()?, &session) {
+            Ok(artwork) => return Some(artwork),
+            Err(err) => warn(format!("{:?} image rendering failed: {}", backend, err)),
+        }
+    }
+
+    if needs_probe && !session.capabilities.kitty && !session.capabilities.sixel {
+        warn(format!(
+            "Image mode requested but the terminal{} did not report kitty or sixel graphics \
+             support. Falling back to ASCII output.",
+            session
+                .capabilities
+                .terminal_name
+                .as_ref()
+                .map(|name| format!(" ({})", name))
+                .unwrap_or_default()
+        ));
+    }
+    None
+}
+
+fn render_with(
+    backend: Backend,
+    image: &PreparedImage,
+    session: &Session,
+) -> Result<Artwork, String> {
+    let display = session.display;
+    let layout = compute_layout(image, display);
+    let mut preamble = String::new();
+
+    let upload = Upload {
+        png: &image.png,
+        width: image.width,
+        height: image.height,
+        id: session.image_id,
+        already_loaded: session.capabilities.kitty_image_loaded,
+        transmission: session.transmission,
+        png_path: image.png_path.as_deref(),
+        animation: image.animation.as_ref(),
+    };
+
+    match backend {
+        Backend::Kitty
+            if display
+                .image_unicode_placeholders
+                .unwrap_or(session.multiplexer.is_active()) =>
+        {
+            let lines = kitty::render_placeholders(
+                &upload,
+                layout.columns,
+                layout.rows,
+                session.multiplexer,
+                &mut preamble,
+            )?;
+            return Ok(Artwork {
+                preamble,
+                lines: offset_text_artwork(lines, display),
+                exact_colors: true,
+                padding: false,
+            });
+        }
+        Backend::Kitty => {
+            kitty::render(
+                &upload,
+                layout.offset_columns,
+                layout.vertical_offset_rows,
+                session.multiplexer,
+                &mut preamble,
+            )?;
+        }
+        Backend::Sixel => {
+            move_cursor(
+                &mut preamble,
+                layout.offset_columns,
+                layout.vertical_offset_rows,
+            );
+            sixel::render(image.pixels()?, &mut preamble)?;
+        }
+        Backend::HalfBlock => {
+            let lines = halfblock::render(image.pixels()?, layout.columns, layout.rows);
+            return Ok(Artwork {
+                preamble,
+                lines: offset_text_artwork(lines, display),
+                exact_colors: false,
+                padding: false,
+            });
+        }
+        Backend::Ascii => unreachable!("ASCII is handled by the caller"),
+    }
+
+    let padding = " ".repeat(layout.pad_columns);
+    Ok(Artwork {
+        preamble,
+        lines: vec![padding; layout.pad_rows.max(1)],
+        exact_colors: false,
+        padding: true,
+    })
+}
+
+/// Derive a kitty image id (non-zero, 24-bit so it fits in a placeholder
+/// color) from everything that determines the uploaded pixels.
+///
+/// The same logo therefore maps to the same id on every run, which lets a
+/// later run in the same terminal reuse the upload instead of resending it.
+fn kitty_image_id(path: &str, display: &DisplayConfig) -> Option<u32> {
+    let mut key = CacheKey::new();
+    key.push_file(Path::new(path)).ok()?;
+    let (width, height) = prepare::target_size(Path::new(path), display);
+    key.push_u64(width.unwrap_or(0) as u64)
+        .push_u64(height.unwrap_or(0) as u64)
+        .push_u64(display.image_animate.unwrap_or(true) as u64);
+    let hash = std::hash::Hasher::finish(&key);
+    Some(((hash ^ (hash >> 24) ^ (hash >> 48)) as u32 & 0xFF_FFFF).max(1))
+}
+
+fn is_remote_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|name| env::var_os(name).is_some())
+}
+
+/// Apply the configured offsets and the gap column to artwork made of text cells
+fn offset_text_artwork(mut lines: Vec<String>, display: &DisplayConfig) -> Vec<String> {
+    let indent = " ".repeat(display.image_offset_columns.unwrap_or(0).max(0) as usize);
+    for line in lines.iter_mut() {
+        line.insert_str(0, &indent);
+        line.push_str(&" ".repeat(DEFAULT_GAP_COLUMNS));
+    }
+    let offset_rows = display.image_offset_rows.unwrap_or(0).max(0) as usize;
+    lines.splice(0..0, std::iter::repeat_n(String::new(), offset_rows));
+    lines
+}
+
+fn compute_layout(image: &PreparedImage, display: &DisplayConfig) -> Layout {
+    let (width, height) = (image.width, image.height);
+    let (char_width, char_height) =
+        terminal::cell_size().unwrap_or((DEFAULT_CHAR_WIDTH, DEFAULT_CHAR_HEIGHT));
+
+    let columns = ((width as f32 / char_width).ceil() as usize).max(1);
+    let rows = display
+        .image_rows
+        .unwrap_or_else(|| ((height as f32 / char_height).ceil() as usize).max(1));
+    let offset_columns = display.image_offset_columns.unwrap_or(0);
+    let offset_rows = display.image_offset_rows.unwrap_or(0);
+    let auto_pad_columns = display
+        .image_padding_columns
+        .unwrap_or(columns + DEFAULT_GAP_COLUMNS);
+
+    Layout {
+        columns,
+        rows,
+        pad_columns: adjust_with_offset(auto_pad_columns, offset_columns, 0),
+        pad_rows: adjust_with_offset(rows, offset_rows, 1),
+        offset_columns,
+        vertical_offset_rows: compute_vertical_offset_rows(rows) + offset_rows,
+    }
+}
+
+/// Relative cursor movement (CSI C/D/B/A) used to position cursor-drawn images
+fn move_cursor(output: &mut String, columns: i32, rows: i32) {
+    if columns > 0 {
+        output.push_str(&format!("\x1b[{}C", columns));
+    } else if columns < 0 {
+        output.push_str(&format!("\x1b[{}D", -columns));
+    }
+    if rows > 0 {
+        output.push_str(&format!("\x1b[{}B", rows));
+    } else if rows < 0 {
+        output.push_str(&format!("\x1b[{}A", -rows));
+    }
+}
+
+fn compute_vertical_offset_rows(pad_rows: usize) -> i32 {
+    if pad_rows == 0 {
+        return 0;
+    }
+
+    let offset = (pad_rows as f32 * 0.08).round() as i32;
+    offset.max(1)
+}
+
+fn adjust_with_offset(base: usize, delta: i32, min_value: usize) -> usize {
+    let adjusted = base as i32 + delta;
+    if adjusted < min_value as i32 {
+        min_value
+    } else {
+        adjusted as usize
+    }
+}
@@ -0,0 +1,114 @@
+//! Terminal multiplexer detection and DCS passthrough wrapping
+
+use std::env;
+
+/// GNU screen truncates DCS strings beyond this many bytes
+const SCREEN_CHUNK: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Multiplexer {
+    None,
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    pub fn detect() -> Multiplexer {
+        if env::var_os("TMUX").is_some_and(|value| !value.is_empty()) {
+            Multiplexer::Tmux
+        } else if env::var_os("STY").is_some_and(|value| !value.is_empty()) {
+            Multiplexer::Screen
+        } else {
+            Multiplexer::None
+        }
+    }
+
+    pub fn is_active(self) -> bool {
+        self != Multiplexer::None
+    }
+
+    /// Wrap an escape sequence so the multiplexer forwards it untouched to
+    /// the outer terminal.
+    ///
+    /// tmux needs `allow-passthrough on` (tmux 3.3+) and expects every ESC in
+    /// the payload to be doubled. screen has no escaping, so the payload is
+    /// split into short DCS strings with every inner ST (`ESC \`) split
+    /// across two of them so it cannot terminate the wrapper early.
+    pub fn wrap(self, sequence: &str) -> String {
+        match self {
+            Multiplexer::None => sequence.to_string(),
+            Multiplexer::Tmux => {
+                let mut wrapped = String::with_capacity(sequence.len() + 16);
+                wrapped.push_str("\x1bPtmux;");
+                wrapped.push_str(&sequence.replace('\x1b', "\x1b\x1b"));
+                wrapped.push_str("\x1b\\");
+                wrapped
+            }
+            Multiplexer::Screen => {
+                let mut wrapped = String::with_capacity(sequence.len() * 2);
+                let mut rest = sequence;
+                while !rest.is_empty() {
+                    let mut end = rest.len().min(SCREEN_CHUNK);
+                    while !rest.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    // Split inner string terminators between their ESC and '\'
+                    if let Some(st) = rest[..end].find("\x1b\\") {
+                        end = st + 1;
+                    }
+                    let (chunk, tail) = rest.split_at(end);
+                    wrapped.push_str("\x1bP");
+                    wrapped.push_str(chunk);
+                    wrapped.push_str("\x1b\\");
+                    rest = tail;
+                }
+                wrapped
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KITTY: &str = "\x1b_Ga=T,f=100;AAAA\x1b\\";
+
+    #[test]
+    fn no_multiplexer_leaves_sequences_alone() {
+        assert_eq!(Multiplexer::None.wrap(KITTY), KITTY);
+    }
+
+    #[test]
+    fn tmux_doubles_every_escape() {
+        assert_eq!(
+            Multiplexer::Tmux.wrap(KITTY),
+            "\x1bPtmux;\x1b\x1b_Ga=T,f=100;AAAA\x1b\x1b\\\x1b\\"
+        );
+    }
+
+    #[test]
+    fn screen_splits_inner_terminators() {
+        assert_eq!(
+            Multiplexer::Screen.wrap(KITTY),
+            "\x1bP\x1b_Ga=T,f=100;AAAA\x1b\x1b\\\x1bP\\\x1b\\"
+        );
+    }
+
+    #[test]
+    fn screen_splits_long_payloads() {
+        let payload = "a".repeat(SCREEN_CHUNK * 2 + 3);
+        let expected = format!(
+            "\x1bP{0}\x1b\\\x1bP{0}\x1b\\\x1bPaaa\x1b\\",
+            "a".repeat(SCREEN_CHUNK)
+        );
+        assert_eq!(Multiplexer::Screen.wrap(&payload), expected);
+    }
+
+    #[test]
+    fn screen_chunks_end_on_char_boundaries() {
+        let payload = format!("{}é", "a".repeat(SCREEN_CHUNK - 1));
+        let expected = format!("\x1bP{}\x1b\\\x1bPé\x1b\\", "a".repeat(SCREEN_CHUNK - 1));
+        assert_eq!(Multiplexer::Screen.wrap(&payload), expected);
+    }
+}
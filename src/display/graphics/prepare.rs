@@ -0,0 +1,439 @@
+//! Loading, resizing and caching of the logo image
+
+use super::{DEFAULT_CHAR_HEIGHT, DEFAULT_CHAR_WIDTH};
+use crate::config::DisplayConfig;
+use crate::utils::cache::{self, CacheKey};
+use crate::utils::terminal;
+use image::codecs::{gif::GifDecoder, webp::WebPDecoder};
+use image::{imageops::FilterType, AnimationDecoder, DynamicImage, ImageOutputFormat, RgbaImage};
+use resvg::{tiny_skia, usvg};
+use std::cell::OnceCell;
+use std::fs::{self, File};
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+const CACHE_NAMESPACE: &str = "images";
+const RESIZE_FILTER: FilterType = FilterType::Lanczos3;
+/// Part of the cache key, so changing the filter invalidates old entries
+const RESIZE_FILTER_NAME: &str = "lanczos3";
+/// Upper bound on decoded animation frames, to keep huge GIFs in check
+const MAX_FRAMES: usize = 256;
+/// Browsers play frames with a delay of 10ms or less at this speed, and so
+/// do we: such files rely on it.
+const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+/// Rows an SVG logo is rendered to when no size is configured
+const DEFAULT_SVG_ROWS: usize = 20;
+
+/// The logo at its final size, encoded as PNG.
+///
+/// Pixels are only decoded when a backend actually needs them, so a cache
+/// hit for a kitty transmission never touches the image decoder.
+pub struct PreparedImage {
+    pub png: Vec<u8>,
+    /// File on disk holding exactly `png` (the source or the cache entry)
+    pub png_path: Option<PathBuf>,
+    pub width: u32,
+    pub height: u32,
+    /// Remaining frames when the source is animated and animation is enabled
+    pub animation: Option<Animation>,
+    decoded: OnceCell<DynamicImage>,
+}
+
+pub struct Animation {
+    /// Display time of the first frame (the one in `PreparedImage::png`)
+    pub first_delay_ms: u32,
+    pub frames: Vec<Frame>,
+}
+
+pub struct Frame {
+    pub png: Vec<u8>,
+    pub png_path: Option<PathBuf>,
+    pub delay_ms: u32,
+}
+
+impl PreparedImage {
+    pub fn pixels(&self) -> Result<&DynamicImage, String> {
+        if let Some(image) = self.decoded.get() {
+            return Ok(image);
+        }
+        let image = image::load_from_memory(&self.png)
+            .map_err(|err| format!("Failed to decode cached image: {}", err))?;
+        Ok(self.decoded.get_or_init(|| image))
+    }
+}
+
+pub fn prepare(path: &str, display: &DisplayConfig) -> Result<PreparedImage, String> {
+    let source = Path::new(path);
+    let (target_width, target_height) = target_size(source, display);
+    let extension = source
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let animate =
+        display.image_animate.unwrap_or(true) && matches!(extension.as_str(), "gif" | "webp");
+
+    // A PNG shown at its natural size can be used as-is
+    if extension == "png" && target_width.is_none() && target_height.is_none() {
+        let png =
+            fs::read(source).map_err(|err| format!("Failed to open image '{}': {}", path, err))?;
+        return from_png(png, Some(source.to_path_buf()), None)
+            .ok_or_else(|| format!("Failed to read PNG header of '{}'", path));
+    }
+
+    let mut key = CacheKey::new();
+    key.push_file(source)
+        .map_err(|err| format!("Failed to open image '{}': {}", path, err))?;
+    key.push_u64(target_width.unwrap_or(0) as u64)
+        .push_u64(target_height.unwrap_or(0) as u64)
+        .push_str(RESIZE_FILTER_NAME)
+        .push_u64(animate as u64);
+    let cache_name = key.to_hex();
+    let cache_file = format!("{}.png", cache_name);
+
+    // The first frame is written last, so its presence means the entry is complete
+    if let Some(png) = cache::read_entry(CACHE_NAMESPACE, &cache_file) {
+        let png_path = cache::entry_path(CACHE_NAMESPACE, &cache_file);
+        if let Some(mut prepared) = from_png(png, png_path, None) {
+            if animate {
+                prepared.animation = read_cached_animation(&cache_name);
+            }
+            return Ok(prepared);
+        }
+    }
+
+    let mut frames = if animate {
+        decode_frames(source, &extension)
+            .map_err(|err| format!("Failed to decode animation '{}': {}", path, err))?
+    } else {
+        Vec::new()
+    };
+    let (image, first_delay_ms) = if is_svg(source) {
+        // Rasterized at the target size directly instead of being resized
+        let image = rasterize_svg(source, target_width, target_height)
+            .map_err(|err| format!("Failed to render SVG '{}': {}", path, err))?;
+        (image, DEFAULT_FRAME_DELAY_MS)
+    } else {
+        let (image, first_delay_ms) = if frames.is_empty() {
+            (open_image(source)?, DEFAULT_FRAME_DELAY_MS)
+        } else {
+            frames.remove(0)
+        };
+        (
+            resize_image(image, target_width, target_height),
+            first_delay_ms,
+        )
+    };
+
+    let animation = if frames.is_empty() {
+        None
+    } else {
+        let frames = frames
+            .into_iter()
+            .enumerate()
+            .map(|(index, (frame, delay_ms))| {
+                let png = encode_png(&resize_image(frame, target_width, target_height))?;
+                let png_path = cache::write_entry(
+                    CACHE_NAMESPACE,
+                    &format!("{}-{}.png", cache_name, index + 1),
+                    &png,
+                );
+                Ok(Frame {
+                    png,
+                    png_path,
+                    delay_ms,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|err| format!("Failed to encode frame of '{}': {}", path, err))?;
+        let animation = Animation {
+            first_delay_ms,
+            frames,
+        };
+        write_cached_animation(&cache_name, &animation);
+        Some(animation)
+    };
+
+    let png =
+        encode_png(&image).map_err(|err| format!("Failed to encode image '{}': {}", path, err))?;
+    let png_path = cache::write_entry(CACHE_NAMESPACE, &cache_file, &png);
+
+    let mut prepared = from_png(png, png_path, Some(image))
+        .ok_or_else(|| format!("Failed to encode image '{}'", path))?;
+    prepared.animation = animation;
+    Ok(prepared)
+}
+
+/// Pixel size the logo is shown at, as far as it is known before decoding.
+///
+/// SVGs are rasterized rather than resized, so without `image_width` and
+/// `image_height` they are rendered to exactly `image_rows` (or
+/// `DEFAULT_SVG_ROWS`) cell heights of the current font, whatever size the
+/// document itself declares.
+pub fn target_size(source: &Path, display: &DisplayConfig) -> (Option<u32>, Option<u32>) {
+    target_size_with(source, display, || {
+        terminal::cell_size()
+            .unwrap_or((DEFAULT_CHAR_WIDTH, DEFAULT_CHAR_HEIGHT))
+            .1
+    })
+}
+
+fn target_size_with(
+    source: &Path,
+    display: &DisplayConfig,
+    char_height: impl FnOnce() -> f32,
+) -> (Option<u32>, Option<u32>) {
+    let (width, height) = (display.image_width, display.image_height);
+    if let (None, None, true) = (width, height, is_svg(source)) {
+        let rows = display.image_rows.unwrap_or(DEFAULT_SVG_ROWS);
+        return (
+            None,
+            Some((rows as f32 * char_height()).round().max(1.0) as u32),
+        );
+    }
+    (width, height)
+}
+
+/// Open any supported image, rasterizing SVGs at their natural size
+pub fn open_image(source: &Path) -> Result<DynamicImage, String> {
+    let image = if is_svg(source) {
+        rasterize_svg(source, None, None)
+    } else {
+        image::open(source).map_err(|err| err.to_string())
+    };
+    image.map_err(|err| format!("Failed to open image '{}': {}", source.display(), err))
+}
+
+fn is_svg(source: &Path) -> bool {
+    source
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("svgz"))
+}
+
+/// Render an SVG to exactly `width` x `height` pixels; a missing dimension
+/// follows the aspect ratio, and both missing means the document's own size.
+fn rasterize_svg(
+    source: &Path,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<DynamicImage, String> {
+    let data = fs::read(source).map_err(|err| err.to_string())?;
+    // `from_data` also takes care of gzip-compressed `.svgz` files
+    let tree =
+        usvg::Tree::from_data(&data, &usvg::Options::default()).map_err(|err| err.to_string())?;
+    let size = tree.size();
+    let (natural_width, natural_height) = (size.width(), size.height());
+
+    let (width, height) = match (width, height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (
+            w,
+            (natural_height * w as f32 / natural_width).round().max(1.0) as u32,
+        ),
+        (None, Some(h)) => (
+            (natural_width * h as f32 / natural_height).round().max(1.0) as u32,
+            h,
+        ),
+        (None, None) => (
+            natural_width.ceil().max(1.0) as u32,
+            natural_height.ceil().max(1.0) as u32,
+        ),
+    };
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| format!("invalid size {}x{}", width, height))?;
+    let transform = tiny_skia::Transform::from_scale(
+        width as f32 / natural_width,
+        height as f32 / natural_height,
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // tiny-skia stores premultiplied alpha
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| "failed to convert rendered pixels".to_string())
+}
+
+/// Decode every frame of an animated GIF or WebP, composited to full size.
+///
+/// Returns no frames for still WebP images so the caller uses the regular decoder.
+fn decode_frames(source: &Path, extension: &str) -> Result<Vec<(DynamicImage, u32)>, String> {
+    let reader = BufReader::new(File::open(source).map_err(|err| err.to_string())?);
+    let frames = match extension {
+        "gif" => GifDecoder::new(reader)
+            .map_err(|err| err.to_string())?
+            .into_frames(),
+        "webp" => {
+            let decoder = WebPDecoder::new(reader).map_err(|err| err.to_string())?;
+            if !decoder.has_animation() {
+                return Ok(Vec::new());
+            }
+            decoder.into_frames()
+        }
+        _ => return Ok(Vec::new()),
+    };
+
+    let frames = frames
+        .take(MAX_FRAMES)
+        .map(|frame| {
+            let frame = frame.map_err(|err| err.to_string())?;
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let delay_ms = numerator / denominator.max(1);
+            let delay_ms = if delay_ms <= 10 {
+                DEFAULT_FRAME_DELAY_MS
+            } else {
+                delay_ms
+            };
+            Ok((DynamicImage::ImageRgba8(frame.into_buffer()), delay_ms))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(frames)
+}
+
+/// The manifest lists one delay per frame, starting with the first frame
+fn write_cached_animation(cache_name: &str, animation: &Animation) {
+    let manifest: String = std::iter::once(animation.first_delay_ms)
+        .chain(animation.frames.iter().map(|frame| frame.delay_ms))
+        .map(|delay| format!("{}\n", delay))
+        .collect();
+    cache::write_entry(
+        CACHE_NAMESPACE,
+        &format!("{}.frames", cache_name),
+        manifest.as_bytes(),
+    );
+}
+
+fn read_cached_animation(cache_name: &str) -> Option<Animation> {
+    let manifest = cache::read_entry(CACHE_NAMESPACE, &format!("{}.frames", cache_name))?;
+    let delays: Vec<u32> = String::from_utf8_lossy(&manifest)
+        .lines()
+        .map(|line| line.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let (first_delay_ms, rest) = delays.split_first()?;
+
+    let frames = rest
+        .iter()
+        .enumerate()
+        .map(|(index, &delay_ms)| {
+            let file_name = format!("{}-{}.png", cache_name, index + 1);
+            Some(Frame {
+                png: cache::read_entry(CACHE_NAMESPACE, &file_name)?,
+                png_path: cache::entry_path(CACHE_NAMESPACE, &file_name),
+                delay_ms,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Animation {
+        first_delay_ms: *first_delay_ms,
+        frames,
+    })
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|err| err.to_string())?;
+    Ok(png)
+}
+
+fn from_png(
+    png: Vec<u8>,
+    png_path: Option<PathBuf>,
+    decoded: Option<DynamicImage>,
+) -> Option<PreparedImage> {
+    let (width, height) = png_dimensions(&png)?;
+    let prepared = PreparedImage {
+        png,
+        png_path,
+        width,
+        height,
+        animation: None,
+        decoded: OnceCell::new(),
+    };
+    if let Some(image) = decoded {
+        let _ = prepared.decoded.set(image);
+    }
+    Some(prepared)
+}
+
+/// Read width and height from the IHDR chunk without decoding the image
+fn png_dimensions(png: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if png.len() < 24 || !png.starts_with(SIGNATURE) || &png[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png[20..24].try_into().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+fn resize_image(image: DynamicImage, width: Option<u32>, height: Option<u32>) -> DynamicImage {
+    match (width, height) {
+        (Some(w), Some(h)) => image.resize_exact(w, h, RESIZE_FILTER),
+        (Some(w), None) => {
+            let ratio = w as f32 / image.width() as f32;
+            let h = ((image.height() as f32 * ratio).round().max(1.0)) as u32;
+            image.resize_exact(w, h, RESIZE_FILTER)
+        }
+        (None, Some(h)) => {
+            let ratio = h as f32 / image.height() as f32;
+            let w = ((image.width() as f32 * ratio).round().max(1.0)) as u32;
+            image.resize_exact(w, h, RESIZE_FILTER)
+        }
+        (None, None) => image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn display(options: &str) -> DisplayConfig {
+        parse_config(&format!(
+            "[display]\nascii_color = \"blue\"\nitems = []\n{}",
+            options
+        ))
+        .unwrap()
+        .display
+    }
+
+    #[test]
+    fn svgs_follow_the_cell_height() {
+        let svg = Path::new("logo.svg");
+        assert_eq!(
+            target_size_with(svg, &display(""), || 17.0),
+            (None, Some(340))
+        );
+        assert_eq!(
+            target_size_with(svg, &display("image_rows = 5"), || 21.5),
+            (None, Some(108))
+        );
+    }
+
+    #[test]
+    fn configured_sizes_win() {
+        let svg = Path::new("logo.SVGZ");
+        assert_eq!(
+            target_size_with(svg, &display("image_width = 64\nimage_rows = 5"), || 20.0),
+            (Some(64), None)
+        );
+        // Bitmaps keep their size unless one is configured
+        let png = Path::new("logo.png");
+        assert_eq!(target_size_with(png, &display(""), || 20.0), (None, None));
+        assert_eq!(
+            target_size_with(png, &display("image_height = 90"), || 20.0),
+            (None, Some(90))
+        );
+    }
+}
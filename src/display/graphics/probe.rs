@@ -0,0 +1,181 @@
+//! Terminal graphics capability probing
+
+use super::multiplexer::Multiplexer;
+use crate::utils::terminal::{self, find_da1, Tty};
+use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
+use base64::Engine;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs};
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+/// Arbitrary ids used for the kitty graphics queries so we can match the replies.
+const KITTY_QUERY_ID: u32 = 31;
+const KITTY_TEMP_QUERY_ID: u32 = 32;
+
+#[derive(Debug, Default, Clone)]
+pub struct Capabilities {
+    pub kitty: bool,
+    pub sixel: bool,
+    /// The image id asked about in `ProbeOptions` is still stored by the terminal
+    pub kitty_image_loaded: bool,
+    /// The terminal accepts temporary-file transmission (`t=t`)
+    pub kitty_temp_files: bool,
+    /// Terminal name and version as reported by XTVERSION, if answered
+    pub terminal_name: Option<String>,
+}
+
+pub struct ProbeOptions {
+    pub multiplexer: Multiplexer,
+    /// Image id whose presence in the terminal's image store should be checked
+    pub kitty_image_id: Option<u32>,
+    /// Test whether temporary-file transmission is accepted
+    pub kitty_temp_files: bool,
+}
+
+/// Ask the terminal which graphics protocols it speaks.
+///
+/// The kitty graphics query, XTVERSION and DA1 are sent in one write. Every
+/// terminal answers DA1, so its reply marks the end of the conversation and
+/// unsupported queries cost nothing beyond the round trip. Inside a
+/// multiplexer the kitty query is passed through to the outer terminal while
+/// DA1 is answered by the multiplexer itself.
+pub fn detect_capabilities(options: &ProbeOptions) -> Capabilities {
+    let mut capabilities = Capabilities::default();
+    let multiplexer = options.multiplexer;
+
+    if matches!(
+        env::var("SWIFTFETCH_FORCE_KITTY"),
+        Ok(v) if v == "1" || v.eq_ignore_ascii_case("true")
+    ) {
+        capabilities.kitty = true;
+        return capabilities;
+    }
+
+    if !terminal::stdout_is_tty() {
+        return capabilities;
+    }
+
+    let Some(mut tty) = Tty::open() else {
+        return capabilities;
+    };
+
+    // The main query goes last: kitty answers in order, so once its reply is
+    // in, the replies to everything before it are too.
+    let mut kitty_queries = String::new();
+
+    // A virtual placement is invisible, so placing the image by id is a
+    // side-effect free way to learn whether it is still loaded (ENOENT if not)
+    if let Some(image_id) = options.kitty_image_id {
+        kitty_queries.push_str(&format!("\x1b_Ga=p,U=1,i={},c=1,r=1,q=1\x1b\\", image_id));
+    }
+
+    let temp_probe = if options.kitty_temp_files {
+        write_temp_probe()
+    } else {
+        None
+    };
+    if let Some(path) = temp_probe.as_ref() {
+        kitty_queries.push_str(&format!(
+            "\x1b_Gi={},s=1,v=1,a=q,t=t,f=24;{}\x1b\\",
+            KITTY_TEMP_QUERY_ID,
+            BASE64_ENGINE.encode(path.to_string_lossy().as_bytes())
+        ));
+    }
+
+    kitty_queries.push_str(&format!(
+        "\x1b_Gi={},s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\",
+        KITTY_QUERY_ID
+    ));
+
+    let request = format!("{}\x1b[>0q\x1b[c", multiplexer.wrap(&kitty_queries));
+    // The multiplexer answers DA1 before the outer terminal's kitty reply can
+    // arrive, so there the kitty reply has to be waited for explicitly.
+    let kitty_reply = format!("\x1b_Gi={};", KITTY_QUERY_ID);
+    let is_complete = |bytes: &[u8]| {
+        contains_da1_reply(bytes)
+            && (!multiplexer.is_active() || String::from_utf8_lossy(bytes).contains(&kitty_reply))
+    };
+    let response = tty.query(request.as_bytes(), is_complete, PROBE_TIMEOUT);
+    drop(tty);
+
+    let response = String::from_utf8_lossy(&response);
+    capabilities.kitty = response.contains(&format!("\x1b_Gi={};OK", KITTY_QUERY_ID));
+    capabilities.sixel = da1_attributes(&response).any(|attr| attr == "4");
+    capabilities.terminal_name = xtversion(&response);
+
+    if let Some(image_id) = options.kitty_image_id {
+        capabilities.kitty_image_loaded =
+            capabilities.kitty && !response.contains(&format!("\x1b_Gi={};", image_id));
+    }
+    if let Some(path) = temp_probe {
+        capabilities.kitty_temp_files =
+            response.contains(&format!("\x1b_Gi={};OK", KITTY_TEMP_QUERY_ID));
+        // The terminal deletes the file after reading it; clean up if it did not
+        let _ = fs::remove_file(path);
+    }
+
+    capabilities
+}
+
+/// Write a 1x1 RGB pixel into a temp file for the `t=t` query
+fn write_temp_probe() -> Option<PathBuf> {
+    let path = env::temp_dir().join(format!(
+        "swiftfetch-tty-graphics-protocol-probe-{}.rgb",
+        std::process::id()
+    ));
+    fs::write(&path, [0u8; 3]).ok()?;
+    Some(path)
+}
+
+fn contains_da1_reply(bytes: &[u8]) -> bool {
+    find_da1(&String::from_utf8_lossy(bytes)).is_some()
+}
+
+fn da1_attributes(response: &str) -> impl Iterator<Item = &str> {
+    find_da1(response).unwrap_or("").split(';')
+}
+
+/// Extract the `DCS > | text ST` XTVERSION reply
+fn xtversion(response: &str) -> Option<String> {
+    let start = response.find("\x1bP>|")? + 4;
+    let end = response[start..].find("\x1b\\")? + start;
+    Some(response[start..end].to_string()).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sixel_from_da1_attributes() {
+        let attributes: Vec<&str> = da1_attributes("\x1b[?62;4;22c").collect();
+        assert_eq!(attributes, ["62", "4", "22"]);
+        // `4` must be a whole attribute, not part of one
+        assert!(!da1_attributes("\x1b[?64;22c").any(|attr| attr == "4"));
+        assert!(da1_attributes("garbage").all(str::is_empty));
+    }
+
+    #[test]
+    fn xtversion_reply() {
+        let response = "\x1b_Gi=31;OK\x1b\\\x1bP>|WezTerm 20240203\x1b\\\x1b[?62;4c";
+        assert_eq!(xtversion(response).as_deref(), Some("WezTerm 20240203"));
+        assert_eq!(
+            xtversion("\x1bP>|foot(1.16.2)\x1b\\").as_deref(),
+            Some("foot(1.16.2)")
+        );
+    }
+
+    #[test]
+    fn xtversion_split_or_empty() {
+        assert_eq!(xtversion("\x1bP>|WezTerm 2024"), None);
+        assert_eq!(xtversion("\x1bP>|\x1b\\"), None);
+        assert_eq!(xtversion("\x1b[?62;4c"), None);
+    }
+
+    #[test]
+    fn da1_completes_the_reply() {
+        assert!(contains_da1_reply(b"\x1bP>|kitty\x1b\\\x1b[?62;4c"));
+        assert!(!contains_da1_reply(b"\x1bP>|kitty\x1b\\\x1b[?62;"));
+    }
+}
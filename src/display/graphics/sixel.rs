@@ -0,0 +1,98 @@
+//! DEC sixel encoding
+
+use color_quant::NeuQuant;
+use image::DynamicImage;
+use std::fmt::Write;
+
+const MAX_COLORS: usize = 256;
+/// NeuQuant sampling factor: 1 is slowest/best, 30 fastest. 10 is the usual default.
+const SAMPLE_FACTOR: i32 = 10;
+const ALPHA_CUTOFF: u8 = 128;
+
+pub fn render(image: &DynamicImage, output: &mut String) -> Result<(), String> {
+    let rgba = image.to_rgba8();
+    let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+    if width == 0 || height == 0 {
+        return Err("Image is empty".to_string());
+    }
+
+    let quantizer = NeuQuant::new(SAMPLE_FACTOR, MAX_COLORS, rgba.as_raw());
+    let indices: Vec<Option<usize>> = rgba
+        .pixels()
+        .map(|pixel| (pixel[3] >= ALPHA_CUTOFF).then(|| quantizer.index_of(&pixel.0)))
+        .collect();
+
+    // P2=1 leaves unset pixels transparent; raster attributes give 1:1 aspect
+    write!(output, "\x1bP0;1;0q\"1;1;{};{}", width, height).map_err(write_error)?;
+
+    let palette = quantizer.color_map_rgb();
+    for (index, rgb) in palette.chunks_exact(3).enumerate() {
+        write!(
+            output,
+            "#{};2;{};{};{}",
+            index,
+            rgb[0] as u32 * 100 / 255,
+            rgb[1] as u32 * 100 / 255,
+            rgb[2] as u32 * 100 / 255
+        )
+        .map_err(write_error)?;
+    }
+
+    let color_count = palette.len() / 3;
+    let mut band_bits = vec![0u8; width * color_count];
+    let mut used = vec![false; color_count];
+
+    for band_top in (0..height).step_by(6) {
+        band_bits.iter_mut().for_each(|bits| *bits = 0);
+        used.iter_mut().for_each(|flag| *flag = false);
+
+        for row in band_top..(band_top + 6).min(height) {
+            let bit = 1u8 << (row - band_top);
+            for x in 0..width {
+                if let Some(color) = indices[row * width + x] {
+                    band_bits[color * width + x] |= bit;
+                    used[color] = true;
+                }
+            }
+        }
+
+        let mut first = true;
+        for color in (0..color_count).filter(|&color| used[color]) {
+            if !first {
+                output.push('$');
+            }
+            first = false;
+
+            write!(output, "#{}", color).map_err(write_error)?;
+            encode_runs(&band_bits[color * width..(color + 1) * width], output)?;
+        }
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    Ok(())
+}
+
+/// Emit one color's sixel row with `!count` run-length compression
+fn encode_runs(bits: &[u8], output: &mut String) -> Result<(), String> {
+    let mut iter = bits.iter().peekable();
+    while let Some(&value) = iter.next() {
+        let mut run = 1;
+        while iter.peek() == Some(&&value) {
+            iter.next();
+            run += 1;
+        }
+
+        let glyph = (b'?' + value) as char;
+        if run > 3 {
+            write!(output, "!{}{}", run, glyph).map_err(write_error)?;
+        } else {
+            output.extend(std::iter::repeat_n(glyph, run));
+        }
+    }
+    Ok(())
+}
+
+fn write_error(_: std::fmt::Error) -> String {
+    "Failed to write sixel escape sequence".to_string()
+}
@@ -0,0 +1,217 @@
+//! OSC 8 hyperlinks, which make values clickable in terminals that support them
+
+use super::color::ColorDepth;
+use super::strip_escapes;
+use crate::collectors::registry::SystemInfo;
+use crate::config::{ConfigEntry, DisplayConfig};
+use crate::data::{OsInfo, UserInfo};
+use crate::utils::terminal;
+use crate::warnings::warn;
+use std::{env, path::Path};
+
+/// Whether to emit hyperlinks, following the `hyperlinks` mode (`auto`,
+/// `always` or `never`). Plain output never carries them, and `auto` only
+/// looks for support on a terminal.
+pub fn enabled(display: &DisplayConfig, depth: ColorDepth, terminal: bool) -> bool {
+    if depth == ColorDepth::None {
+        return false;
+    }
+    match display.hyperlinks.as_deref().unwrap_or("auto") {
+        "always" => true,
+        "never" => false,
+        mode => {
+            if mode != "auto" {
+                warn(format!(
+                    "Unknown hyperlinks mode '{}' (expected auto, always or never)",
+                    mode
+                ));
+            }
+            terminal && detect()
+        }
+    }
+}
+
+/// Guess hyperlink support from the environment. Terminals that do not know
+/// OSC 8 mostly ignore it, but some print it, so unknown ones get none.
+fn detect() -> bool {
+    if let Some(force) = env::var_os("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if !terminal::stdout_is_tty() {
+        return false;
+    }
+
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    // tmux and screen only pass them on when configured to
+    if matches!(var("TERM_PROGRAM").as_str(), "tmux" | "screen") {
+        return false;
+    }
+    if matches!(
+        var("TERM_PROGRAM").as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "rio"
+    ) {
+        return true;
+    }
+    if var("VTE_VERSION")
+        .parse::<u32>()
+        .is_ok_and(|version| version >= 5000)
+    {
+        return true;
+    }
+    if [
+        "WT_SESSION",
+        "KONSOLE_VERSION",
+        "KITTY_WINDOW_ID",
+        "DOMTERM",
+    ]
+    .iter()
+    .any(|name| env::var_os(name).is_some())
+    {
+        return true;
+    }
+    let term = var("TERM");
+    [
+        "xterm-kitty",
+        "alacritty",
+        "foot",
+        "wezterm",
+        "xterm-ghostty",
+    ]
+    .iter()
+    .any(|known| term.starts_with(known))
+}
+
+/// `text` linking to `url`. Control characters in `url`, which may come
+/// from os-release or command output, are percent-encoded so they cannot
+/// end the sequence early.
+pub fn wrap(url: &str, text: &str) -> String {
+    let mut safe = String::with_capacity(url.len());
+    for ch in url.chars() {
+        if ch.is_control() {
+            let mut bytes = [0; 4];
+            for byte in ch.encode_utf8(&mut bytes).bytes() {
+                safe.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            safe.push(ch);
+        }
+    }
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", safe, text)
+}
+
+/// Link target of an item showing `value`: its `link` template, or else the
+/// os-release home page for `os`, the root directory for `disk_usage` and the
+/// file itself for values that are paths to existing files. Colors in
+/// `value`, such as those of command output, are not part of the link.
+pub fn target(entry: &ConfigEntry, collected: &SystemInfo, value: &str) -> Option<String> {
+    let value = &strip_escapes(value);
+    let os = collected.get::<OsInfo>("os").ok();
+    if let Some(template) = &entry.link {
+        return expand(template, os, value);
+    }
+
+    if entry.r#type == "default" {
+        match entry.value.as_str() {
+            "os" => return os?.home_url.clone().or_else(|| os?.bug_report_url.clone()),
+            "disk_usage" => return Some(file_url("/", collected)),
+            _ => {}
+        }
+    }
+
+    let path = shellexpand::tilde(value.trim());
+    (path.starts_with('/') && Path::new(path.as_ref()).exists()).then(|| file_url(&path, collected))
+}
+
+/// Fill in a `link` template; `None` when it is empty or names a URL that
+/// os-release does not have. A leading `{value}` is the URL itself and is
+/// kept as it is, anywhere else it is percent-encoded.
+fn expand(template: &str, os: Option<&OsInfo>, value: &str) -> Option<String> {
+    let mut url = match template.strip_prefix("{value}") {
+        Some(rest) => format!("{}{}", value.trim(), rest),
+        None => template.to_string(),
+    };
+    url = url.replace("{value}", &encode(value.trim(), false));
+    for (placeholder, field) in [
+        ("{home_url}", os.and_then(|os| os.home_url.as_deref())),
+        (
+            "{bug_report_url}",
+            os.and_then(|os| os.bug_report_url.as_deref()),
+        ),
+    ] {
+        if url.contains(placeholder) {
+            url = url.replace(placeholder, field?);
+        }
+    }
+    (!url.is_empty()).then_some(url)
+}
+
+/// `file://` URL of an absolute path, naming this host as the spec asks
+fn file_url(path: &str, collected: &SystemInfo) -> String {
+    let host = collected
+        .get::<UserInfo>("user")
+        .map_or("", |user| user.hostname.as_str());
+    format!("file://{}{}", host, encode(path, true))
+}
+
+/// Percent-encode everything but unreserved characters (and `/` in paths)
+fn encode(text: &str, path: bool) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if path => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn entry(item: &str) -> ConfigEntry {
+        parse_config(&format!(
+            "[display]\nascii_color = \"blue\"\n[[display.items]]\n{}",
+            item
+        ))
+        .unwrap()
+        .display
+        .items
+        .remove(0)
+    }
+
+    #[test]
+    fn link_values_leave_out_colors() {
+        let collected = SystemInfo::default();
+        let search =
+            entry("type = \"command\"\nvalue = \"\"\nlink = \"https://example.org/?q={value}\"");
+        assert_eq!(
+            target(&search, &collected, "\x1b[1;38;5;196mred dog\x1b[0m").as_deref(),
+            Some("https://example.org/?q=red%20dog")
+        );
+
+        let url = entry("type = \"command\"\nvalue = \"\"\nlink = \"{value}\"");
+        assert_eq!(
+            target(&url, &collected, "\x1b[4mhttps://example.org/a b\x1b[0m").as_deref(),
+            Some("https://example.org/a b")
+        );
+    }
+
+    #[test]
+    fn wrap_encodes_control_characters() {
+        assert_eq!(
+            wrap("https://example.org/\x1b\\\x07", "x"),
+            "\x1b]8;;https://example.org/%1B\\%07\x1b\\x\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn expand_needs_the_os_release_urls_it_names() {
+        assert_eq!(expand("{home_url}", None, "x"), None);
+        assert_eq!(expand("", None, "x"), None);
+    }
+}
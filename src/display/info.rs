@@ -0,0 +1,830 @@
+//! The info column: items rendered into styled lines, sections drawn as boxes
+
+use super::color::{self, ColorDepth};
+use super::gradient::Gradient;
+use super::hyperlink;
+use super::style::Style;
+use super::{
+    bar, fit_to_width, get_output_value, pad_to_width, value_number, value_percent, visible_width,
+};
+use crate::collectors::registry::SystemInfo;
+use crate::config::{ColorSpec, Config, ConfigEntry, DisplayConfig};
+use crate::warnings::warn;
+use std::collections::HashMap;
+
+/// Spaces between info columns, the same as between logo and info
+const COLUMN_GAP: usize = 2;
+/// Palette rows of a `colors` item without a `value`
+const DEFAULT_PALETTE_ROWS: &str = "normal bright";
+
+/// Corners, horizontal and vertical piece of a section border
+struct Border {
+    top_left: &'static str,
+    top_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+    horizontal: &'static str,
+    vertical: &'static str,
+}
+
+impl Border {
+    fn from_name(name: &str) -> Border {
+        let pieces = match name {
+            "single" => ["┌", "┐", "└", "┘", "─", "│"],
+            "double" => ["╔", "╗", "╚", "╝", "═", "║"],
+            "rounded" => ["╭", "╮", "╰", "╯", "─", "│"],
+            "ascii" => ["+", "+", "+", "+", "-", "|"],
+            other => {
+                warn(format!(
+                    "Unknown section border '{}' (expected single, double, rounded or ascii), using single",
+                    other
+                ));
+                ["┌", "┐", "└", "┘", "─", "│"]
+            }
+        };
+        Border {
+            top_left: pieces[0],
+            top_right: pieces[1],
+            bottom_left: pieces[2],
+            bottom_right: pieces[3],
+            horizontal: pieces[4],
+            vertical: pieces[5],
+        }
+    }
+}
+
+/// How keys line up within a list of items
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeyAlign {
+    None,
+    Left,
+    Right,
+}
+
+impl KeyAlign {
+    fn from_name(name: &str) -> KeyAlign {
+        match name {
+            "none" => KeyAlign::None,
+            "left" => KeyAlign::Left,
+            "right" => KeyAlign::Right,
+            other => {
+                warn(format!(
+                    "Unknown key_align '{}' (expected left, right or none), leaving keys unaligned",
+                    other
+                ));
+                KeyAlign::None
+            }
+        }
+    }
+}
+
+/// Items with a `gpu` item replaced by one item per GPU, inside sections too
+pub fn expand_gpus(items: &[ConfigEntry], gpu_count: usize) -> Vec<ConfigEntry> {
+    let mut expanded = Vec::with_capacity(items.len());
+    for entry in items {
+        if entry.r#type == "section" {
+            expanded.push(ConfigEntry {
+                items: entry
+                    .items
+                    .as_deref()
+                    .map(|children| expand_gpus(children, gpu_count)),
+                ..entry.clone()
+            });
+        } else if entry.value == "gpu" {
+            for gpu_idx in 0..gpu_count {
+                expanded.push(ConfigEntry {
+                    value: format!("gpu{}", gpu_idx + 1),
+                    ..entry.clone()
+                });
+            }
+        } else {
+            expanded.push(entry.clone());
+        }
+    }
+    expanded
+}
+
+/// Number of columns: the highest `column` set on the items, or else
+/// `display.columns`
+fn column_count(items: &[ConfigEntry], display: &DisplayConfig) -> usize {
+    let explicit = items.iter().filter_map(|entry| entry.column).max();
+    explicit.or(display.columns).unwrap_or(1).max(1)
+}
+
+/// Column of each item: from `column` on the items when any has one (an
+/// item stays in the column of the one before it), or else balanced by the
+/// `heights` of the items
+fn assign_columns(items: &[ConfigEntry], heights: &[usize], count: usize) -> Vec<usize> {
+    if !items.iter().any(|entry| entry.column.is_some()) {
+        return balance(heights, count);
+    }
+    let mut column = 0;
+    items
+        .iter()
+        .map(|entry| {
+            if let Some(start) = entry.column {
+                column = start.max(1) - 1;
+            }
+            column
+        })
+        .collect()
+}
+
+/// Split blocks of the given heights, in order, into at most `count`
+/// columns with the lowest possible tallest column. Returns the column of
+/// each block.
+fn balance(heights: &[usize], count: usize) -> Vec<usize> {
+    let pack = |limit: usize| {
+        let mut columns = Vec::with_capacity(heights.len());
+        let (mut column, mut used) = (0, 0);
+        for &height in heights {
+            if used > 0 && used + height > limit {
+                column += 1;
+                used = 0;
+            }
+            used += height;
+            columns.push(column);
+        }
+        columns
+    };
+
+    // The smallest height limit that still fits in `count` columns
+    let (mut low, mut high) = (
+        heights.iter().copied().max().unwrap_or(0),
+        heights.iter().sum::<usize>(),
+    );
+    while low < high {
+        let middle = (low + high) / 2;
+        if pack(middle).last().is_some_and(|&column| column >= count) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    pack(low)
+}
+
+/// A section's items and title, framed into a box once the width it shares
+/// with the sections next to it is known
+struct Section<'e> {
+    entry: &'e ConfigEntry,
+    title: String,
+    children: Vec<Option<String>>,
+}
+
+impl Section<'_> {
+    /// Width inside the borders and their padding
+    fn content_width(&self) -> usize {
+        let title_width = if self.title.is_empty() {
+            0
+        } else {
+            visible_width(&self.title) + 2
+        };
+        self.children
+            .iter()
+            .flatten()
+            .map(|line| visible_width(line))
+            .max()
+            .unwrap_or(0)
+            .max(title_width)
+    }
+}
+
+/// The rendered form of one item
+enum Block<'e> {
+    Lines(Vec<Option<String>>),
+    Section(Section<'e>),
+}
+
+impl Block<'_> {
+    fn rows(&self) -> usize {
+        match self {
+            Block::Lines(lines) => lines.len(),
+            Block::Section(section) => section.children.len() + 2,
+        }
+    }
+}
+
+pub struct InfoRenderer<'a> {
+    config: &'a Config,
+    collected: &'a SystemInfo,
+    depth: ColorDepth,
+    reset: &'static str,
+    separator: &'a str,
+    default_overflow: &'a str,
+    key_align: KeyAlign,
+    hyperlinks: bool,
+    /// Color codes by spec, to avoid repeated parsing
+    color_cache: HashMap<String, String>,
+}
+
+impl<'a> InfoRenderer<'a> {
+    pub fn new(
+        config: &'a Config,
+        collected: &'a SystemInfo,
+        depth: ColorDepth,
+        terminal: bool,
+    ) -> Self {
+        InfoRenderer {
+            config,
+            collected,
+            depth,
+            // Plain output carries no escape sequences at all
+            reset: if depth == ColorDepth::None {
+                ""
+            } else {
+                "\x1b[0m"
+            },
+            separator: config.display.separator.as_deref().unwrap_or(": "),
+            default_overflow: config.display.overflow.as_deref().unwrap_or("truncate"),
+            hyperlinks: hyperlink::enabled(&config.display, depth, terminal),
+            key_align: KeyAlign::from_name(config.display.key_align.as_deref().unwrap_or("none")),
+            color_cache: HashMap::new(),
+        }
+    }
+
+    fn plain(&self) -> bool {
+        self.depth == ColorDepth::None
+    }
+
+    /// Escape sequence for a color spec, resolved against `[colors]`
+    pub fn color(&mut self, spec: &ColorSpec) -> String {
+        let resolve = || Style::resolve(spec, &self.config.colors).sgr(self.depth);
+        match spec {
+            ColorSpec::Spec(key) => {
+                if let Some(code) = self.color_cache.get(key) {
+                    return code.clone();
+                }
+                let code = resolve();
+                self.color_cache.insert(key.clone(), code.clone());
+                code
+            }
+            ColorSpec::Gradient { .. } | ColorSpec::Table { .. } => resolve(),
+        }
+    }
+
+    fn color_or_reset(&mut self, spec: Option<&ColorSpec>) -> String {
+        spec.map(|spec| self.color(spec))
+            .unwrap_or_else(|| self.reset.to_string())
+    }
+
+    /// Like `render`, but with the items split into side-by-side columns:
+    /// explicitly through `column` on the items, or balanced by height
+    /// across `display.columns`. `width` is shared by all columns.
+    pub fn render_columns(
+        &mut self,
+        items: &[ConfigEntry],
+        width: Option<usize>,
+    ) -> Vec<Option<String>> {
+        let count = column_count(items, &self.config.display);
+        if count == 1 {
+            return self.render(items, width);
+        }
+
+        let column_width =
+            width.map(|width| (width.saturating_sub(COLUMN_GAP * (count - 1)) / count).max(1));
+        // Keys line up across all columns
+        let key_width = self.key_width(items);
+        let blocks: Vec<Block> = items
+            .iter()
+            .map(|entry| self.render_item(entry, column_width, key_width))
+            .collect();
+
+        let heights: Vec<usize> = blocks.iter().map(Block::rows).collect();
+        let assignment = assign_columns(items, &heights, count);
+
+        let mut column_blocks: Vec<Vec<Block>> = (0..count).map(|_| Vec::new()).collect();
+        for (block, column) in blocks.into_iter().zip(assignment) {
+            column_blocks[column].push(block);
+        }
+        let mut columns: Vec<Vec<Option<String>>> = column_blocks
+            .into_iter()
+            .map(|blocks| self.join_blocks(blocks))
+            .collect();
+        columns.retain(|column| !column.is_empty());
+
+        // Every column but the last is padded to its widest line
+        let widths: Vec<usize> = columns
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .flatten()
+                    .map(|line| visible_width(line))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+
+        (0..rows)
+            .map(|row| {
+                // Rows blank in every column stay blank
+                let last = columns
+                    .iter()
+                    .rposition(|column| matches!(column.get(row), Some(Some(_))))?;
+                let mut line = String::new();
+                for (index, column) in columns.iter().enumerate().take(last + 1) {
+                    let cell = column.get(row).and_then(Option::as_deref).unwrap_or("");
+                    if index == last {
+                        line.push_str(cell);
+                    } else {
+                        line.push_str(&pad_to_width(cell, widths[index]));
+                        line.push_str(&" ".repeat(COLUMN_GAP));
+                    }
+                }
+                Some(line)
+            })
+            .collect()
+    }
+
+    /// One entry per output row (`None` for blank rows), each fitted to
+    /// `width` columns when the width is known
+    pub fn render(&mut self, items: &[ConfigEntry], width: Option<usize>) -> Vec<Option<String>> {
+        let key_width = self.key_width(items);
+        self.render_items(items, width, key_width)
+    }
+
+    fn render_items(
+        &mut self,
+        items: &[ConfigEntry],
+        width: Option<usize>,
+        key_width: usize,
+    ) -> Vec<Option<String>> {
+        let blocks = items
+            .iter()
+            .map(|entry| self.render_item(entry, width, key_width))
+            .collect();
+        self.join_blocks(blocks)
+    }
+
+    fn render_item<'e>(
+        &mut self,
+        entry: &'e ConfigEntry,
+        width: Option<usize>,
+        key_width: usize,
+    ) -> Block<'e> {
+        let mut lines = Vec::new();
+        if entry.r#type == "section" {
+            return Block::Section(self.render_section(entry, width, key_width));
+        } else if entry.value.is_empty() && entry.r#type != "colors" {
+            lines.push(None);
+        } else {
+            self.render_entry(entry, width, key_width, &mut lines);
+        }
+        Block::Lines(lines)
+    }
+
+    /// Lines of the blocks stacked in one column, with every section box as
+    /// wide as the widest of them
+    fn join_blocks(&mut self, blocks: Vec<Block>) -> Vec<Option<String>> {
+        let content_width = blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Section(section) => Some(section.content_width()),
+                Block::Lines(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        for block in blocks {
+            match block {
+                Block::Lines(block_lines) => lines.extend(block_lines),
+                Block::Section(section) => self.frame_section(&section, content_width, &mut lines),
+            }
+        }
+        lines
+    }
+
+    fn separator<'e>(&'e self, entry: &'e ConfigEntry) -> &'e str {
+        entry.separator.as_deref().unwrap_or(self.separator)
+    }
+
+    /// Width of the prefix, key and separator of items shown as `key: value`
+    fn key_part_width(&self, entry: &ConfigEntry) -> Option<usize> {
+        let keyed = !matches!(entry.r#type.as_str(), "text" | "section")
+            && (!entry.value.is_empty() || entry.r#type == "colors")
+            && !entry.key.is_empty()
+            && entry.key != "user_info";
+        keyed.then(|| {
+            visible_width(entry.prefix.as_deref().unwrap_or(""))
+                + visible_width(&entry.key)
+                + visible_width(self.separator(entry))
+        })
+    }
+
+    /// Width the key parts of `items` are padded to under `key_align`,
+    /// counting the items inside sections so their keys line up too
+    fn key_width(&self, items: &[ConfigEntry]) -> usize {
+        if self.key_align == KeyAlign::None {
+            return 0;
+        }
+        items
+            .iter()
+            .filter_map(|entry| match entry.items.as_deref() {
+                Some(children) if entry.r#type == "section" => Some(self.key_width(children)),
+                _ => {
+                    let width = self.key_part_width(entry)?;
+                    Some(width.max(entry.min_width.unwrap_or(0)))
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn render_entry(
+        &mut self,
+        entry: &ConfigEntry,
+        width: Option<usize>,
+        key_width: usize,
+        lines: &mut Vec<Option<String>>,
+    ) {
+        let reset = self.reset;
+        let key_color_code = self.color_or_reset(entry.color.as_ref());
+
+        // A matching threshold overrides the item's value color
+        let threshold_color = entry.thresholds.as_ref().and_then(|thresholds| {
+            let number = value_number(entry, self.collected)?;
+            thresholds
+                .iter()
+                .find(|threshold| threshold.contains(number))
+                .map(|threshold| &threshold.color)
+        });
+        // `user_info` is bold unless the config styles it
+        let user_info_style = ColorSpec::Spec("bold".to_string());
+        let default_style =
+            (entry.r#type == "default" && entry.value == "user_info").then_some(&user_info_style);
+        let value_color_code = self.color_or_reset(
+            threshold_color
+                .or(entry.value_color.as_ref())
+                .or(default_style),
+        );
+
+        // The styled key part and the style of the value that follows it
+        let item_prefix = entry.prefix.as_deref().unwrap_or("");
+        let (prefix, prefix_width, value_style) = match self.key_part_width(entry) {
+            Some(key_part_width) => {
+                let key_part = format!(
+                    "{}{}{}{}{}",
+                    key_color_code,
+                    item_prefix,
+                    entry.key,
+                    self.separator(entry),
+                    reset
+                );
+                let target = key_width.max(entry.min_width.unwrap_or(0));
+                let padding = " ".repeat(target.saturating_sub(key_part_width));
+                let prefix = if self.key_align == KeyAlign::Right {
+                    format!("{}{}", padding, key_part)
+                } else {
+                    format!("{}{}", key_part, padding)
+                };
+                (prefix, key_part_width.max(target), value_color_code)
+            }
+            // Items without a key show the prefix before their value
+            None => {
+                let style = if entry.r#type == "text" {
+                    key_color_code
+                } else {
+                    value_color_code
+                };
+                let prefix = if item_prefix.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}{}", style, item_prefix, reset)
+                };
+                (prefix, visible_width(item_prefix), style)
+            }
+        };
+        let suffix = entry.suffix.as_deref().unwrap_or("");
+
+        let (raw_lines, link) = if entry.r#type == "colors" {
+            let rows = match entry.value.as_str() {
+                "" => DEFAULT_PALETTE_ROWS,
+                rows => rows,
+            };
+            let rows = color::palette_rows(rows, entry.symbol.as_deref(), entry.width, self.depth);
+            (rows, None)
+        } else {
+            // Command output may carry its own colors
+            let value = self
+                .depth
+                .downsample(&get_output_value(entry, self.collected));
+            let link = self
+                .hyperlinks
+                .then(|| hyperlink::target(entry, self.collected, &value))
+                .flatten();
+            let placement = bar::Placement::from_config(entry.bar.as_deref());
+            let lines = match placement.zip(value_percent(entry, self.collected, &value)) {
+                Some((placement, percent)) => {
+                    let display = &self.config.display;
+                    let full_color = display
+                        .bar_color_full
+                        .as_ref()
+                        .map(|spec| self.color(spec))
+                        .unwrap_or_else(|| value_style.clone());
+                    let empty_color = display
+                        .bar_color_empty
+                        .as_ref()
+                        .map(|spec| self.color(spec))
+                        .unwrap_or_else(|| value_style.clone());
+                    let restore = format!("{}{}", reset, value_style);
+                    let bar = bar::render(
+                        percent,
+                        entry.width,
+                        display,
+                        &full_color,
+                        &empty_color,
+                        &restore,
+                    );
+                    vec![placement.apply(&value, &bar)]
+                }
+                None => vec![value],
+            };
+            (lines, link)
+        };
+
+        let overflow = entry.overflow.as_deref().unwrap_or(self.default_overflow);
+        let value_lines: Vec<String> = match width {
+            Some(width) => {
+                let available = width
+                    .saturating_sub(prefix_width + visible_width(suffix))
+                    .max(1);
+                raw_lines
+                    .iter()
+                    .flat_map(|raw_line| fit_to_width(raw_line, available, overflow))
+                    .collect()
+            }
+            None => raw_lines,
+        };
+
+        // Text items such as headers may use a gradient instead
+        let text_gradient = (entry.r#type == "text" && !self.plain())
+            .then(|| Gradient::from_spec(entry.color.as_ref()?, &self.config.colors))
+            .flatten();
+        let (value_lines, value_style) = match text_gradient {
+            Some(gradient) => (gradient.paint(&value_lines, self.depth), String::new()),
+            None => (value_lines, value_style),
+        };
+
+        for (index, value_line) in value_lines.iter().enumerate() {
+            let mut line = String::new();
+            if index == 0 {
+                line.push_str(&prefix);
+            } else {
+                // Continuation lines start in the value column
+                line.push_str(&" ".repeat(prefix_width));
+            }
+            line.push_str(&value_style);
+            match &link {
+                // Wrapped values link every line, so each can be clicked
+                Some(url) => line.push_str(&hyperlink::wrap(url, value_line)),
+                None => line.push_str(value_line),
+            }
+            if index + 1 == value_lines.len() {
+                line.push_str(suffix);
+            }
+            line.push_str(reset);
+            lines.push(Some(line));
+        }
+    }
+
+    /// The items and title of a section, fitted inside its borders
+    fn render_section<'e>(
+        &mut self,
+        entry: &'e ConfigEntry,
+        width: Option<usize>,
+        key_width: usize,
+    ) -> Section<'e> {
+        // Borders and padding take two columns on either side
+        let inner_width = width.map(|width| width.saturating_sub(4).max(1));
+        let children = self.render_items(
+            entry.items.as_deref().unwrap_or_default(),
+            inner_width,
+            key_width,
+        );
+
+        let title = match inner_width {
+            // The title sits between `─ ` and ` ─` on the top border
+            Some(inner) if !entry.value.is_empty() => {
+                fit_to_width(&entry.value, inner.saturating_sub(2).max(1), "truncate")
+                    .swap_remove(0)
+            }
+            _ => entry.value.clone(),
+        };
+
+        Section {
+            entry,
+            title,
+            children,
+        }
+    }
+
+    /// A box `content_width` wide around the section's items, with the
+    /// section's `value` as its title
+    fn frame_section(
+        &mut self,
+        section: &Section,
+        content_width: usize,
+        lines: &mut Vec<Option<String>>,
+    ) {
+        let reset = self.reset;
+        let entry = section.entry;
+        let border_name = entry
+            .border
+            .as_deref()
+            .or(self.config.display.section_border.as_deref())
+            .unwrap_or("single");
+        let border = Border::from_name(border_name);
+        let border_style = self.color_or_reset(entry.color.as_ref());
+
+        let (title, children) = (&section.title, &section.children);
+        let title_width = visible_width(title);
+        let content_width = content_width.max(section.content_width());
+        let top = if title.is_empty() {
+            border.horizontal.repeat(content_width + 2)
+        } else {
+            format!(
+                "{} {} {}",
+                border.horizontal,
+                title,
+                border
+                    .horizontal
+                    .repeat(content_width.saturating_sub(title_width + 1))
+            )
+        };
+        let top = format!("{}{}{}", border.top_left, top, border.top_right);
+        let bottom = format!(
+            "{}{}{}",
+            border.bottom_left,
+            border.horizontal.repeat(content_width + 2),
+            border.bottom_right
+        );
+
+        // A gradient runs along the top and bottom borders and down the sides
+        let rows = children.len() + 2;
+        let gradient = (!self.plain())
+            .then(|| Gradient::from_spec(entry.color.as_ref()?, &self.config.colors))
+            .flatten();
+        let (top, bottom, sides) = match gradient {
+            Some(gradient) => {
+                let mut painted = gradient.paint(&[top, bottom], self.depth).into_iter();
+                (
+                    painted.next().unwrap_or_default(),
+                    painted.next().unwrap_or_default(),
+                    gradient.frame_sides(content_width + 4, rows, self.depth),
+                )
+            }
+            None => (
+                top,
+                bottom,
+                vec![(border_style.clone(), border_style.clone()); rows],
+            ),
+        };
+
+        lines.push(Some(format!("{}{}{}", border_style, top, reset)));
+        for (child, (left, right)) in children.iter().zip(&sides[1..]) {
+            let content = child.as_deref().unwrap_or("");
+            lines.push(Some(format!(
+                "{}{}{} {}{} {}{}{}",
+                left,
+                border.vertical,
+                reset,
+                pad_to_width(content, content_width),
+                reset,
+                right,
+                border.vertical,
+                reset
+            )));
+        }
+        lines.push(Some(format!("{}{}{}", border_style, bottom, reset)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn config(display: &str) -> Config {
+        parse_config(&format!("[display]\nascii_color = \"blue\"\n{}", display)).unwrap()
+    }
+
+    fn render(config: &Config) -> Vec<String> {
+        let collected = SystemInfo::default();
+        let mut renderer = InfoRenderer::new(config, &collected, ColorDepth::None, false);
+        renderer
+            .render_columns(&config.display.items, None)
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect()
+    }
+
+    /// Text items, with `column` set on the ones given one
+    fn text_items(columns: &[Option<usize>]) -> Vec<ConfigEntry> {
+        let mut toml = String::new();
+        for column in columns {
+            toml.push_str("[[display.items]]\ntype = \"text\"\nvalue = \"x\"\n");
+            if let Some(column) = column {
+                toml.push_str(&format!("column = {}\n", column));
+            }
+        }
+        config(&toml).display.items
+    }
+
+    #[test]
+    fn balance_one_column() {
+        assert_eq!(balance(&[3, 1, 2], 1), [0, 0, 0]);
+    }
+
+    #[test]
+    fn balance_two_columns_with_a_tall_first_block() {
+        assert_eq!(balance(&[5, 1, 1, 1, 1, 1], 2), [0, 1, 1, 1, 1, 1]);
+        assert_eq!(balance(&[1, 1, 1, 1, 6], 2), [0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn balance_three_columns_minimizes_the_tallest() {
+        // A limit of 4 would need a fourth column
+        assert_eq!(balance(&[4, 1, 1, 3, 2, 2], 3), [0, 0, 1, 1, 2, 2]);
+        assert_eq!(balance(&[2, 2, 2, 2, 2, 2], 3), [0, 0, 1, 1, 2, 2]);
+        // Fewer blocks than columns leave the rest empty
+        assert_eq!(balance(&[3, 1], 3), [0, 1]);
+    }
+
+    #[test]
+    fn explicit_columns_stick_for_the_items_after_them() {
+        let items = text_items(&[None, None, Some(2), None, Some(1), None]);
+        assert_eq!(assign_columns(&items, &[1; 6], 2), [0, 0, 1, 1, 0, 0]);
+        // Without any `column` the heights decide
+        let items = text_items(&[None, None, None]);
+        assert_eq!(assign_columns(&items, &[2, 1, 1], 2), [0, 1, 1]);
+    }
+
+    #[test]
+    fn column_count_prefers_explicit_columns() {
+        let mut config = config("columns = 3\nitems = []");
+        assert_eq!(column_count(&config.display.items, &config.display), 3);
+        config.display.items = text_items(&[None, Some(2)]);
+        assert_eq!(column_count(&config.display.items, &config.display), 2);
+    }
+
+    #[test]
+    fn colors_item_without_a_value_draws_the_default_palette() {
+        let config = config(
+            "[[display.items]]\ntype = \"colors\"\nsymbol = \"#\"\nwidth = 1\n\n\
+             [[display.items]]\ntype = \"text\"\n",
+        );
+        let collected = SystemInfo::default();
+        let mut renderer = InfoRenderer::new(&config, &collected, ColorDepth::Ansi16, false);
+        let lines = renderer.render(&config.display.items, None);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].as_deref().unwrap().contains("\x1b[30m#\x1b[31m#"));
+        assert!(lines[1].as_deref().unwrap().contains("\x1b[90m#\x1b[91m#"));
+        // An empty item of any other type is still a spacer
+        assert_eq!(lines[2], None);
+    }
+
+    #[test]
+    fn stacked_sections_share_width_and_key_column() {
+        let config = config(
+            r#"key_align = "right"
+
+[[display.items]]
+type = "section"
+value = "Long"
+
+[[display.items.items]]
+key = "k"
+type = "text"
+value = "a much longer value"
+
+[[display.items.items]]
+key = "key"
+type = "command"
+value = "printf x"
+
+[[display.items]]
+type = "section"
+value = "Short"
+
+[[display.items.items]]
+key = "kernel"
+type = "command"
+value = "printf y"
+"#,
+        );
+        assert_eq!(
+            render(&config),
+            [
+                "┌─ Long ──────────────┐",
+                "│ a much longer value │",
+                "│    key: x           │",
+                "└─────────────────────┘",
+                "┌─ Short ─────────────┐",
+                "│ kernel: y           │",
+                "└─────────────────────┘",
+            ]
+        );
+    }
+}
@@ -0,0 +1,634 @@
+mod ascii_image;
+mod bar;
+mod color;
+pub mod format;
+mod gradient;
+mod graphics;
+mod hyperlink;
+mod info;
+mod os_logo;
+mod style;
+
+pub use self::color::ColorDepth;
+pub use self::style::Style;
+
+use self::gradient::Gradient;
+use crate::collectors::registry::SystemInfo;
+use crate::config::{Config, ConfigEntry};
+use crate::utils::terminal;
+use crate::warnings::warn;
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    iter::Peekable,
+    path::Path,
+    process::Command,
+    str::CharIndices,
+};
+use unicode_width::UnicodeWidthStr;
+
+pub fn get_default_ascii(distro: &str) -> Option<&'static str> {
+    match distro.to_lowercase().as_str() {
+        "arch" => Some(include_str!("../../ascii/arch.txt")),
+        "ubuntu" => Some(include_str!("../../ascii/ubuntu.txt")),
+        "cachyos" => Some(include_str!("../../ascii/cachyos.txt")),
+        "debian" => Some(include_str!("../../ascii/debian.txt")),
+        "fedora" => Some(include_str!("../../ascii/fedora.txt")),
+        "gentoo" => Some(include_str!("../../ascii/gentoo.txt")),
+        "void" => Some(include_str!("../../ascii/void.txt")),
+        "nixos" => Some(include_str!("../../ascii/nixos.txt")),
+        "pika" => Some(include_str!("../../ascii/pika.txt")),
+        _ => None,
+    }
+}
+
+pub fn load_ascii_lines(config: &Config, os_name: &str) -> Vec<String> {
+    let use_default = config.display.use_default_ascii.unwrap_or(true);
+
+    if display_mode(config) == "image_ascii" {
+        if let Some(lines) = load_converted_image(config) {
+            return lines;
+        }
+    }
+
+    match config
+        .display
+        .ascii_path
+        .as_ref()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+    {
+        Some(ascii_path) => {
+            let expanded_path = shellexpand::tilde(ascii_path).to_string();
+            if Path::new(&expanded_path).exists() {
+                if let Ok(file) = fs::File::open(&expanded_path) {
+                    return io::BufReader::new(file)
+                        .lines()
+                        .map_while(Result::ok)
+                        .collect();
+                }
+            }
+            // If file does not exist or failed to open
+            if use_default {
+                if let Some(default_ascii) = get_default_ascii(os_name) {
+                    return default_ascii.lines().map(String::from).collect();
+                }
+            }
+            vec![]
+        }
+        None if use_default => {
+            if let Some(default_ascii) = get_default_ascii(os_name) {
+                return default_ascii.lines().map(String::from).collect();
+            }
+            vec![]
+        }
+        _ => vec![],
+    }
+}
+
+fn display_mode(config: &Config) -> String {
+    config
+        .display
+        .display_mode
+        .as_deref()
+        .unwrap_or("ascii")
+        .to_lowercase()
+}
+
+/// The configured `image_path`, or else the distribution logo named by os-release
+fn resolve_image_path(config: &Config) -> Option<String> {
+    match config.display.image_path.as_ref() {
+        Some(image_path) => Some(shellexpand::tilde(image_path).to_string()),
+        None => os_logo::find().map(|path| path.to_string_lossy().into_owned()),
+    }
+}
+
+fn load_converted_image(config: &Config) -> Option<Vec<String>> {
+    let Some(expanded_path) = resolve_image_path(config) else {
+        warn(
+            "Image ASCII mode was requested but 'image_path' was not set and no os-release logo \
+             was found. Falling back to ASCII output.",
+        );
+        return None;
+    };
+
+    let options = ascii_image::AsciiImageOptions {
+        columns: config.display.image_ascii_width,
+        charset: config.display.image_ascii_charset.as_deref(),
+        colored: config.display.image_ascii_colored.unwrap_or(false),
+        dither: config.display.image_ascii_dither.unwrap_or(false),
+    };
+
+    match ascii_image::convert(&expanded_path, &options) {
+        Ok(lines) => Some(lines),
+        Err(err) => {
+            warn(format!(
+                "Image to ASCII conversion failed (falling back to ASCII): {}",
+                err
+            ));
+            None
+        }
+    }
+}
+
+/// Skip the rest of an escape sequence whose `ESC` was just read: SGR and
+/// other CSI sequences up to their final byte, OSC sequences such as
+/// hyperlinks up to `BEL` or `ESC \`
+fn skip_escape(chars: &mut Peekable<CharIndices>) {
+    match chars.peek().map(|(_, next)| *next) {
+        Some('[') => {
+            chars.next();
+            for (_, next) in chars.by_ref() {
+                if ('@'..='~').contains(&next) {
+                    break;
+                }
+            }
+        }
+        Some(']') => {
+            chars.next();
+            while let Some((_, next)) = chars.next() {
+                if next == '\x07' {
+                    break;
+                }
+                if next == '\x1b' {
+                    chars.next_if(|(_, after)| *after == '\\');
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Display width of `text`, ignoring any embedded escape sequences
+fn visible_width(text: &str) -> usize {
+    if !text.contains('\x1b') {
+        return UnicodeWidthStr::width(text);
+    }
+
+    let mut width = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, ch)) = chars.next() {
+        if ch == '\x1b' {
+            skip_escape(&mut chars);
+            continue;
+        }
+        width += unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+    }
+    width
+}
+
+/// `text` without its escape sequences
+fn strip_escapes(text: &str) -> String {
+    if !text.contains('\x1b') {
+        return text.to_string();
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, ch)) = chars.next() {
+        if ch == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            stripped.push(ch);
+        }
+    }
+    stripped
+}
+
+/// Byte offset in `text` after `width` display columns, never splitting
+/// escape sequences (which take no columns)
+fn width_offset(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        if ch == '\x1b' {
+            skip_escape(&mut chars);
+            continue;
+        }
+        let char_width = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+        if used + char_width > width {
+            return index;
+        }
+        used += char_width;
+    }
+    text.len()
+}
+
+/// Make `value` fit in `width` columns: `truncate` cuts it with an ellipsis,
+/// `wrap` breaks it into several lines (at spaces where possible) and `none`
+/// leaves it alone.
+fn fit_to_width(value: &str, width: usize, overflow: &str) -> Vec<String> {
+    if visible_width(value) <= width {
+        return vec![value.to_string()];
+    }
+
+    match overflow {
+        "wrap" => wrap_to_width(value, width),
+        "truncate" => {
+            let cut = width_offset(value, width.saturating_sub(1));
+            carry_styles(vec![format!("{}…", &value[..cut])])
+        }
+        "none" => vec![value.to_string()],
+        other => {
+            warn(format!(
+                "Unknown overflow mode '{}' (expected truncate, wrap or none)",
+                other
+            ));
+            vec![value.to_string()]
+        }
+    }
+}
+
+fn wrap_to_width(value: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in value.split(' ') {
+        let word_width = visible_width(word);
+        let needed = if current.is_empty() {
+            word_width
+        } else {
+            current_width + 1 + word_width
+        };
+        if needed <= width {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+            current_width = needed;
+            continue;
+        }
+
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        // Words longer than a whole line are split hard
+        let mut rest = word;
+        while visible_width(rest) > width {
+            let mut cut = width_offset(rest, width);
+            if cut == 0 {
+                // A wide character in a one-column space still has to go somewhere
+                cut = rest.chars().next().map_or(rest.len(), char::len_utf8);
+            }
+            lines.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+        }
+        current.push_str(rest);
+        current_width = visible_width(rest);
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    carry_styles(lines)
+}
+
+/// Reopen the SGR styles still active at the end of each line on the next
+/// one, and close them before the break, so split values keep their colors
+fn carry_styles(lines: Vec<String>) -> Vec<String> {
+    let mut active = String::new();
+    lines
+        .into_iter()
+        .map(|line| {
+            let mut carried = active.clone();
+            carried.push_str(&line);
+
+            let mut chars = line.char_indices().peekable();
+            while let Some((start, ch)) = chars.next() {
+                if ch != '\x1b' {
+                    continue;
+                }
+                skip_escape(&mut chars);
+                let end = chars.peek().map_or(line.len(), |(index, _)| *index);
+                let Some(params) = line[start..end]
+                    .strip_prefix("\x1b[")
+                    .and_then(|sequence| sequence.strip_suffix('m'))
+                else {
+                    continue;
+                };
+                if matches!(params.split(';').next(), Some("" | "0")) {
+                    active.clear();
+                }
+                if !matches!(params, "" | "0") {
+                    active.push_str(&line[start..end]);
+                }
+            }
+
+            if !active.is_empty() {
+                carried.push_str("\x1b[0m");
+            }
+            carried
+        })
+        .collect()
+}
+
+/// Right-pad `text` with spaces up to `width` display columns
+fn pad_to_width(text: &str, width: usize) -> String {
+    let current = visible_width(text);
+    if current >= width {
+        return text.to_string();
+    }
+    let mut padded = String::with_capacity(text.len() + width - current);
+    padded.push_str(text);
+    padded.extend(std::iter::repeat_n(' ', width - current));
+    padded
+}
+
+/// What `render_output` knows about the writer it draws to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// The writer is stdout, which may be a terminal that is probed for
+    /// colors, hyperlinks, graphics support and its size. Other writers get
+    /// no images and only what the rest of the options and the config say.
+    pub terminal: bool,
+    /// Colors to draw with, instead of following the `color` option
+    pub color_depth: Option<ColorDepth>,
+    /// Columns to fit the output in, instead of the terminal's width
+    pub width: Option<usize>,
+}
+
+/// Draw the logo and info lines for `config` to `out`
+pub fn render_output<W: Write>(
+    config: &Config,
+    collected: &SystemInfo,
+    options: &RenderOptions,
+    out: &mut W,
+) -> io::Result<()> {
+    let show_all_gpus = config.display.show_all_gpus.unwrap_or(false);
+    let rendered_items = if show_all_gpus {
+        let gpu_count = collected.gpu().map_or(0, |gpu| gpu.all_gpus.len());
+        info::expand_gpus(&config.display.items, gpu_count)
+    } else {
+        config.display.items.clone()
+    };
+
+    let depth = color::output_depth(&config.display, options);
+    // Plain output carries no escape sequences at all
+    let plain = depth == ColorDepth::None;
+    let reset = if plain { "" } else { "\x1b[0m" };
+
+    let mut renderer = info::InfoRenderer::new(config, collected, depth, options.terminal);
+    let ascii_color_code = renderer.color(&config.display.ascii_color);
+
+    let mut output = String::with_capacity(4096);
+
+    let mut ascii_lines: Vec<String> = Vec::new();
+    // Blank line that keeps the column of a drawn image clear
+    let mut image_padding = None;
+    let display_mode = display_mode(config);
+
+    let use_image_mode = !plain && matches!(display_mode.as_str(), "image" | "kitty");
+
+    if use_image_mode && !options.terminal {
+        warn("Image display mode needs a terminal. Falling back to ASCII output.");
+    } else if use_image_mode {
+        if let Some(expanded_path) = resolve_image_path(config) {
+            if let Some(artwork) = graphics::render_artwork(&config.display, &expanded_path) {
+                if artwork.padding {
+                    image_padding = artwork.lines.first().cloned();
+                }
+                ascii_lines = if artwork.exact_colors {
+                    artwork.lines
+                } else {
+                    artwork
+                        .lines
+                        .iter()
+                        .map(|line| depth.downsample(line))
+                        .collect()
+                };
+
+                if !artwork.preamble.is_empty() {
+                    output.push_str("\x1b[s");
+                    output.push_str(&artwork.preamble);
+                    output.push_str("\x1b[u");
+                }
+            }
+        } else {
+            warn(
+                "Image display mode was requested but 'image_path' was not set and no os-release \
+                 logo was found. Falling back to ASCII output.",
+            );
+        }
+    }
+
+    if ascii_lines.is_empty() {
+        let os_name = collected.os().map_or("", |os| os.name.as_str());
+        ascii_lines = load_ascii_lines(config, os_name)
+            .iter()
+            .map(|line| depth.downsample(line))
+            .collect();
+    }
+
+    if let Some(gradient) = Gradient::from_spec(&config.display.ascii_color, &config.colors) {
+        if !plain {
+            ascii_lines = gradient.paint(&ascii_lines, depth);
+        }
+    }
+
+    let max_ascii_length = ascii_lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+
+    // Info lines are fitted to the space right of the logo, after the
+    // two columns of gap that separate them
+    let info_width = options
+        .width
+        .or_else(|| options.terminal.then(terminal::columns).flatten())
+        .map(|columns| columns.saturating_sub(max_ascii_length + 2).max(1));
+    let info_lines = renderer.render_columns(&rendered_items, info_width);
+    if let Some(padding) = image_padding {
+        if ascii_lines.len() < info_lines.len() {
+            ascii_lines.resize(info_lines.len(), padding);
+        }
+    }
+
+    for row in 0..info_lines.len().max(ascii_lines.len()) {
+        let ascii_line = ascii_lines.get(row).map(String::as_str).unwrap_or("");
+        output.push_str(&ascii_color_code);
+        output.push_str(&pad_to_width(ascii_line, max_ascii_length));
+        // A background or underline from ascii_color stops at the logo
+        output.push_str(reset);
+
+        if let Some(Some(info_line)) = info_lines.get(row) {
+            output.push_str("  ");
+            output.push_str(info_line);
+        }
+        output.push('\n');
+    }
+
+    output.push_str(reset);
+    out.write_all(output.as_bytes())
+}
+
+/// Number behind a built-in value, for `thresholds`: percentages for
+/// `memory`, `swap`, `disk_usage` and `battery`, °C for `cpu_temp`, seconds
+/// for `uptime_seconds` and counts for the package values
+fn value_number(entry: &ConfigEntry, collected: &SystemInfo) -> Option<f64> {
+    if entry.r#type != "default" {
+        return None;
+    }
+    format::number(&collected.field(&entry.value)?)
+}
+
+/// Percentage behind an item's value, for drawing its usage bar. Built-in
+/// values use the collected numbers, anything else the first `N%` in its text.
+fn value_percent(entry: &ConfigEntry, collected: &SystemInfo, value: &str) -> Option<f64> {
+    if entry.r#type == "default" && collected.provides(&entry.value) {
+        return format::percent(&collected.field(&entry.value)?);
+    }
+    bar::parse_percent(value)
+}
+
+fn get_output_value(entry: &ConfigEntry, collected: &SystemInfo) -> String {
+    match entry.r#type.as_str() {
+        "default" => match collected.field(&entry.value) {
+            Some(value) => format::text(&value),
+            // Its collector failed or was not run
+            None if collected.provides(&entry.value) => "Unknown".to_string(),
+            None => "Unknown default value".to_string(),
+        },
+        "text" => entry.value.clone(),
+        "command" => Command::new("sh")
+            .arg("-c")
+            .arg(&entry.value)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_else(|_| "Command failed".to_string()),
+        _ => "Invalid type".to_string(),
+    }
+}
+
+/// Keys every bundled theme defines, in the order they are previewed
+const THEME_KEYS: &[&str] = &[
+    "primary",
+    "secondary",
+    "text",
+    "accent",
+    "success",
+    "warning",
+    "info",
+];
+
+/// Print every available theme with a swatch of its colors (or the color
+/// values themselves in plain output), for `--list-themes`
+pub fn render_theme_list<W: Write>(
+    config: &Config,
+    options: &RenderOptions,
+    out: &mut W,
+) -> io::Result<()> {
+    let depth = color::output_depth(&config.display, options);
+    let themes = crate::theme::all();
+    let name_width = themes
+        .iter()
+        .map(|theme| visible_width(&theme.name))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for theme in &themes {
+        output.push_str(&pad_to_width(&theme.name, name_width));
+        output.push_str("  ");
+
+        // Well-known keys first, then anything extra the theme defines
+        let mut keys: Vec<&str> = THEME_KEYS
+            .iter()
+            .copied()
+            .filter(|key| theme.colors.contains_key(*key))
+            .collect();
+        let mut extra: Vec<&str> = theme
+            .colors
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !THEME_KEYS.contains(key))
+            .collect();
+        extra.sort_unstable();
+        keys.extend(extra);
+
+        if depth == ColorDepth::None {
+            let values: Vec<String> = keys
+                .iter()
+                .map(|key| format!("{}={}", key, theme.colors[*key]))
+                .collect();
+            output.push_str(&values.join(" "));
+        } else {
+            for key in &keys {
+                output.push_str(&Style::resolve(&theme.colors[*key], &theme.colors).sgr(depth));
+                output.push_str("███");
+            }
+            output.push_str("\x1b[0m");
+        }
+
+        if theme.source != "bundled" {
+            output.push_str("  ");
+            output.push_str(&theme.source);
+        }
+        output.push('\n');
+    }
+    out.write_all(output.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_breaks_at_spaces() {
+        assert_eq!(wrap_to_width("aaa bbb ccc", 7), ["aaa bbb", "ccc"]);
+    }
+
+    #[test]
+    fn wrap_reopens_colors_on_continuation_lines() {
+        assert_eq!(
+            wrap_to_width("\x1b[31mred words here\x1b[0m", 9),
+            ["\x1b[31mred words\x1b[0m", "\x1b[31mhere\x1b[0m"]
+        );
+    }
+
+    #[test]
+    fn wrap_splits_palette_rows_without_losing_colors() {
+        assert_eq!(
+            wrap_to_width("\x1b[31m███\x1b[32m███\x1b[0m", 4),
+            ["\x1b[31m███\x1b[32m█\x1b[0m", "\x1b[31m\x1b[32m██\x1b[0m"]
+        );
+    }
+
+    #[test]
+    fn wrap_keeps_wide_characters_whole() {
+        assert_eq!(
+            wrap_to_width("日本語テキスト", 5),
+            ["日本", "語テ", "キス", "ト"]
+        );
+        assert_eq!(wrap_to_width("日本", 1), ["日", "本"]);
+    }
+
+    #[test]
+    fn truncate_closes_open_colors() {
+        assert_eq!(
+            fit_to_width("\x1b[32mabcdefgh", 5, "truncate"),
+            ["\x1b[32mabcd…\x1b[0m"]
+        );
+        assert_eq!(fit_to_width("日本語", 4, "truncate"), ["日…"]);
+    }
+
+    #[test]
+    fn fit_leaves_short_values_and_none_alone() {
+        assert_eq!(
+            fit_to_width("\x1b[1mshort\x1b[0m", 5, "wrap"),
+            ["\x1b[1mshort\x1b[0m"]
+        );
+        assert_eq!(fit_to_width("too long", 3, "none"), ["too long"]);
+    }
+
+    #[test]
+    fn width_ignores_escape_sequences() {
+        assert_eq!(visible_width("\x1b[31m日本\x1b[0m"), 4);
+        assert_eq!(
+            visible_width("\x1b]8;;https://example.org\x1b\\link\x1b]8;;\x1b\\"),
+            4
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Locate the distribution's picture logo from the os-release `LOGO` field
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const OS_RELEASE_PATHS: [&str; 2] = ["/etc/os-release", "/usr/lib/os-release"];
+const EXTENSIONS: [&str; 2] = ["svg", "png"];
+
+/// Resolve `LOGO=<icon name>` through the freedesktop icon theme directories
+/// and `/usr/share/pixmaps`, preferring the largest match.
+///
+/// SVGs count as larger than any PNG since they are rasterized at the
+/// requested size anyway.
+pub fn find() -> Option<PathBuf> {
+    let name = read_logo_name()?;
+    // Some distributions put a path rather than an icon name in LOGO
+    if name.contains('/') {
+        return Path::new(&name).is_file().then(|| PathBuf::from(name));
+    }
+
+    let mut best: Option<(u32, PathBuf)> = None;
+    let mut consider = |path: PathBuf| {
+        let Some(size) = icon_size(&path) else {
+            return;
+        };
+        if best.as_ref().is_none_or(|(best_size, _)| size > *best_size) {
+            best = Some((size, path));
+        }
+    };
+
+    for base in icon_base_dirs() {
+        let Ok(themes) = fs::read_dir(&base) else {
+            continue;
+        };
+        for theme in themes.flatten() {
+            search_theme(&theme.path(), &name, &mut consider);
+        }
+    }
+
+    for pixmaps in ["/usr/share/pixmaps", "/usr/local/share/pixmaps"] {
+        for extension in EXTENSIONS {
+            let path = Path::new(pixmaps).join(format!("{}.{}", name, extension));
+            if path.is_file() {
+                consider(path);
+            }
+        }
+    }
+
+    best.map(|(_, path)| path)
+}
+
+fn read_logo_name() -> Option<String> {
+    let contents = OS_RELEASE_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())?;
+    contents.lines().find_map(|line| {
+        let value = line.strip_prefix("LOGO=")?;
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// `$XDG_DATA_HOME/icons`, `~/.icons` and `icons` under every `$XDG_DATA_DIRS`
+fn icon_base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(data_home) = dirs::data_dir() {
+        dirs.push(data_home.join("icons"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".icons"));
+    }
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("icons")),
+    );
+    dirs
+}
+
+/// Themes nest icons as `<size>/<context>/` (hicolor) or `<context>/<size>/`
+/// (e.g. Adwaita), so both levels below the theme are searched.
+fn search_theme(theme: &Path, name: &str, consider: &mut impl FnMut(PathBuf)) {
+    let Ok(outer) = fs::read_dir(theme) else {
+        return;
+    };
+    for outer in outer.flatten() {
+        let Ok(inner) = fs::read_dir(outer.path()) else {
+            continue;
+        };
+        for inner in inner.flatten() {
+            for extension in EXTENSIONS {
+                let path = inner.path().join(format!("{}.{}", name, extension));
+                if path.is_file() {
+                    consider(path);
+                }
+            }
+        }
+    }
+}
+
+/// Pixel width of a PNG from its header, or `u32::MAX` for an SVG
+fn icon_size(path: &Path) -> Option<u32> {
+    if path.extension().is_some_and(|ext| ext == "svg") {
+        return Some(u32::MAX);
+    }
+    let mut header = [0u8; 24];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if !header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return None;
+    }
+    Some(u32::from_be_bytes([
+        header[16], header[17], header[18], header[19],
+    ]))
+}
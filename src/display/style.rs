@@ -0,0 +1,311 @@
+//! Color specs (`"bold italic #ff8800 on #202020"`, color names, `[colors]`
+//! keys or `{ fg, bg, bold, ... }` tables) turned into SGR sequences
+
+use super::color::{ColorDepth, ANSI16_PALETTE};
+use crate::config::ColorSpec;
+use crate::warnings::warn;
+use std::collections::HashMap;
+
+/// Text attributes accepted in specs, with their SGR codes
+pub const ATTRIBUTES: &[(&str, u8)] = &[
+    ("bold", 1),
+    ("dim", 2),
+    ("italic", 3),
+    ("underline", 4),
+    ("reverse", 7),
+    ("strikethrough", 9),
+];
+
+/// Foreground and background colors plus text attributes
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    /// SGR codes from `ATTRIBUTES`
+    pub attributes: Vec<u8>,
+}
+
+impl Style {
+    /// Parse a spec string: attribute words, a foreground color, and a
+    /// background color after `on`
+    pub fn parse(spec: &str) -> Style {
+        let mut style = Style::default();
+        let mut background = false;
+        for word in spec.split_whitespace() {
+            if word.eq_ignore_ascii_case("on") {
+                background = true;
+            } else if let Some(code) = attribute_code(word) {
+                style.add_attribute(code);
+            } else if background {
+                style.bg = Some(word.to_string());
+            } else {
+                style.fg = Some(word.to_string());
+            }
+        }
+        style
+    }
+
+    fn from_spec(spec: &ColorSpec) -> Style {
+        match spec {
+            ColorSpec::Spec(spec) => Style::parse(spec),
+            // Where gradients are not supported their first color is used
+            ColorSpec::Gradient { gradient, .. } => Style {
+                fg: gradient.first().cloned(),
+                ..Style::default()
+            },
+            ColorSpec::Table { fg, bg, .. } => {
+                let mut style = Style {
+                    fg: fg.clone(),
+                    bg: bg.clone(),
+                    attributes: Vec::new(),
+                };
+                for name in spec.attributes() {
+                    if let Some(code) = attribute_code(name) {
+                        style.add_attribute(code);
+                    }
+                }
+                style
+            }
+        }
+    }
+
+    /// Style of `spec`, where a spec that is a `[colors]` key stands for that
+    /// entry and colors inside a spec may also be keys. An `on <key>`
+    /// background uses the key's foreground color.
+    pub fn resolve(spec: &ColorSpec, colors: &HashMap<String, ColorSpec>) -> Style {
+        if let ColorSpec::Spec(name) = spec {
+            if let Some(named) = colors.get(name.trim()) {
+                return Style::from_spec(named);
+            }
+        }
+
+        let mut style = Style::from_spec(spec);
+        if let Some(named) = style.fg.as_ref().and_then(|fg| colors.get(fg)) {
+            let named = Style::from_spec(named);
+            style.fg = named.fg;
+            style.bg = style.bg.or(named.bg);
+            for code in named.attributes {
+                style.add_attribute(code);
+            }
+        }
+        if let Some(named) = style.bg.as_ref().and_then(|bg| colors.get(bg)) {
+            style.bg = Style::from_spec(named).fg;
+        }
+        style
+    }
+
+    fn add_attribute(&mut self, code: u8) {
+        if !self.attributes.contains(&code) {
+            self.attributes.push(code);
+        }
+    }
+
+    /// A single SGR sequence for this style at `depth`, empty in plain output.
+    /// A style without anything to set resets instead.
+    pub fn sgr(&self, depth: ColorDepth) -> String {
+        if depth == ColorDepth::None {
+            return String::new();
+        }
+
+        let mut params: Vec<String> = self.attributes.iter().map(u8::to_string).collect();
+        params.extend(
+            self.fg
+                .as_deref()
+                .and_then(|fg| color_params(fg, 38, depth)),
+        );
+        params.extend(
+            self.bg
+                .as_deref()
+                .and_then(|bg| color_params(bg, 48, depth)),
+        );
+
+        if params.is_empty() {
+            "\x1b[0m".to_string()
+        } else {
+            format!("\x1b[{}m", params.join(";"))
+        }
+    }
+}
+
+fn attribute_code(name: &str) -> Option<u8> {
+    ATTRIBUTES
+        .iter()
+        .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+}
+
+/// SGR parameters for a color name or `#rrggbb` hex color on layer 38
+/// (foreground) or 48 (background), reduced to what the terminal shows
+pub fn color_params(color: &str, layer: u8, depth: ColorDepth) -> Option<String> {
+    if let Some((r, g, b)) = parse_hex(color) {
+        return depth.rgb_sgr(layer, r, g, b);
+    }
+
+    let code = ansi_color_code(color)?;
+    // Background codes are ten above their foreground counterparts
+    Some(if layer == 48 { code + 10 } else { code }.to_string())
+}
+
+/// RGB value of a hex color, or of a color name as xterm shows it by default
+pub fn color_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    if let Some(rgb) = parse_hex(color) {
+        return Some(rgb);
+    }
+    let index = match ansi_color_code(color)? {
+        code @ 30..=37 => code - 30,
+        code @ 90..=97 => code - 90 + 8,
+        _ => return None,
+    };
+    Some(ANSI16_PALETTE[index as usize])
+}
+
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    if !(color.starts_with('#') && color.len() == 7) {
+        return None;
+    }
+    Some((
+        u8::from_str_radix(&color[1..3], 16).ok()?,
+        u8::from_str_radix(&color[3..5], 16).ok()?,
+        u8::from_str_radix(&color[5..7], 16).ok()?,
+    ))
+}
+
+/// Foreground SGR code of a color name
+fn ansi_color_code(color_name: &str) -> Option<u8> {
+    match color_name.to_lowercase().as_str() {
+        // Standard 8 colors (30-37)
+        "black" => Some(30),
+        "red" => Some(31),
+        "green" => Some(32),
+        "yellow" => Some(33),
+        "blue" => Some(34),
+        "magenta" => Some(35),
+        "cyan" => Some(36),
+        "white" => Some(37),
+
+        // Bright colors (90-97)
+        "bright_black" | "gray" | "grey" => Some(90),
+        "bright_red" => Some(91),
+        "bright_green" => Some(92),
+        "bright_yellow" => Some(93),
+        "bright_blue" => Some(94),
+        "bright_magenta" => Some(95),
+        "bright_cyan" => Some(96),
+        "bright_white" => Some(97),
+
+        // Additional aliases
+        "orange" => Some(91), // bright red
+        "purple" => Some(35), // magenta
+        "violet" => Some(95), // bright magenta
+
+        // The terminal's default color
+        "reset" | "default" => Some(39),
+
+        _ => {
+            // Show available colors if an invalid color is used
+            warn(format!(
+                "Unknown color '{}'. Available colors:\n\
+                 \x20 Standard: black, red, green, yellow, blue, magenta, cyan, white\n\
+                 \x20 Bright:   bright_black, bright_red, bright_green, bright_yellow, bright_blue, bright_magenta, bright_cyan, bright_white\n\
+                 \x20 Aliases:  gray/grey, orange, purple, violet, reset/default\n\
+                 \x20 Or use hex colors like #FF0000\n\
+                 \x20 Styles:   bold, dim, italic, underline, reverse, strikethrough, and `on <color>` for a background",
+                color_name
+            ));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    fn colors() -> HashMap<String, ColorSpec> {
+        parse_config(
+            r##"[display]
+ascii_color = "blue"
+items = []
+
+[colors]
+primary = "bold yellow"
+panel = "cyan on black"
+card = { fg = "#ff8800", bg = "white", italic = true, bold = true }
+"##,
+        )
+        .unwrap()
+        .colors
+    }
+
+    fn sgr(spec: &str, depth: ColorDepth) -> String {
+        Style::resolve(&ColorSpec::Spec(spec.to_string()), &colors()).sgr(depth)
+    }
+
+    #[test]
+    fn attributes_colors_and_background_at_each_depth() {
+        let spec = "bold italic #ff8800 on #202020";
+        assert_eq!(
+            sgr(spec, ColorDepth::TrueColor),
+            "\x1b[1;3;38;2;255;136;0;48;2;32;32;32m"
+        );
+        assert_eq!(
+            sgr(spec, ColorDepth::Ansi256),
+            "\x1b[1;3;38;5;208;48;5;234m"
+        );
+        assert_eq!(sgr(spec, ColorDepth::None), "");
+        assert_eq!(sgr("red on blue", ColorDepth::Ansi256), "\x1b[31;44m");
+    }
+
+    #[test]
+    fn attributes_are_not_repeated() {
+        assert_eq!(
+            sgr("bold BOLD red bold", ColorDepth::TrueColor),
+            "\x1b[1;31m"
+        );
+        assert_eq!(sgr("bold primary", ColorDepth::TrueColor), "\x1b[1;33m");
+    }
+
+    #[test]
+    fn a_key_as_the_whole_spec_is_that_entry() {
+        assert_eq!(sgr("primary", ColorDepth::TrueColor), "\x1b[1;33m");
+        assert_eq!(sgr("panel", ColorDepth::Ansi256), "\x1b[36;40m");
+        assert_eq!(
+            sgr("card", ColorDepth::TrueColor),
+            "\x1b[1;3;38;2;255;136;0;47m"
+        );
+        assert_eq!(sgr("card", ColorDepth::Ansi256), "\x1b[1;3;38;5;208;47m");
+    }
+
+    #[test]
+    fn keys_inside_a_spec() {
+        // The key's attributes and background come along with its color
+        assert_eq!(sgr("italic primary", ColorDepth::TrueColor), "\x1b[3;1;33m");
+        assert_eq!(
+            sgr("underline panel", ColorDepth::TrueColor),
+            "\x1b[4;36;40m"
+        );
+        // Unless the spec sets its own background
+        assert_eq!(sgr("panel on red", ColorDepth::TrueColor), "\x1b[36;41m");
+    }
+
+    #[test]
+    fn a_background_key_uses_its_foreground_color() {
+        assert_eq!(
+            sgr("white on primary", ColorDepth::TrueColor),
+            "\x1b[37;43m"
+        );
+        assert_eq!(
+            sgr("on card", ColorDepth::TrueColor),
+            "\x1b[48;2;255;136;0m"
+        );
+        assert_eq!(sgr("on card", ColorDepth::Ansi256), "\x1b[48;5;208m");
+        assert_eq!(sgr("on card", ColorDepth::None), "");
+    }
+
+    #[test]
+    fn an_empty_style_resets() {
+        assert_eq!(sgr("", ColorDepth::TrueColor), "\x1b[0m");
+        assert_eq!(sgr("", ColorDepth::None), "");
+    }
+}
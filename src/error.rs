@@ -2,20 +2,29 @@
 
 use std::fmt;
 use std::io;
+use std::sync::Arc;
 
-/// Custom error type for swiftfetch operations
-#[derive(Debug)]
+/// Custom error type for swiftfetch operations. It is cheap to clone, so a
+/// collector's error can be handed out every time its output is asked for.
+#[derive(Debug, Clone)]
 pub enum SwiftfetchError {
     /// I/O errors (file reading, command execution)
-    Io(io::Error),
+    Io(Arc<io::Error>),
     /// Parsing errors (invalid data format)
     #[allow(dead_code)]
     Parse(String),
     /// Configuration errors
-    #[allow(dead_code)]
     Config(String),
     /// System detection errors
     Detection(String),
+    /// No collector is registered under this name
+    UnknownCollector(String),
+    /// The collector was not run, so it has no output
+    NotCollected(String),
+    /// The collector depends on itself, directly or through others
+    CircularDependency(String),
+    /// The collector's output was read as another type
+    OutputType(String),
 }
 
 impl fmt::Display for SwiftfetchError {
@@ -25,6 +34,14 @@ impl fmt::Display for SwiftfetchError {
             SwiftfetchError::Parse(msg) => write!(f, "Parse error: {}", msg),
             SwiftfetchError::Config(msg) => write!(f, "Config error: {}", msg),
             SwiftfetchError::Detection(msg) => write!(f, "Detection error: {}", msg),
+            SwiftfetchError::UnknownCollector(name) => write!(f, "No collector called '{}'", name),
+            SwiftfetchError::NotCollected(name) => write!(f, "Collector '{}' did not run", name),
+            SwiftfetchError::CircularDependency(name) => {
+                write!(f, "Collector '{}' has circular dependencies", name)
+            }
+            SwiftfetchError::OutputType(name) => {
+                write!(f, "Collector '{}' has another output type", name)
+            }
         }
     }
 }
@@ -33,7 +50,7 @@ impl std::error::Error for SwiftfetchError {}
 
 impl From<io::Error> for SwiftfetchError {
     fn from(error: io::Error) -> Self {
-        SwiftfetchError::Io(error)
+        SwiftfetchError::Io(Arc::new(error))
     }
 }
 
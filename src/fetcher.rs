@@ -0,0 +1,100 @@
+//! Builder that runs a chosen set of collectors and renders the result
+
+use crate::collectors::registry::{Collector, Registry, SystemInfo};
+use crate::config::Config;
+use crate::display::{self, ColorDepth, RenderOptions};
+use crate::error::Result;
+use std::io::Write;
+
+/// Gathers system information, from every built-in collector unless
+/// `collectors` narrows it down, and renders it like the `swiftfetch`
+/// binary does.
+///
+/// Rendering only looks at the terminal when `terminal` says the output
+/// goes there. Otherwise colors follow `color_depth` (or `color = "always"`
+/// in the config), the width `width`, and images are not drawn.
+#[derive(Clone)]
+pub struct Fetcher {
+    registry: Registry,
+    /// Collectors to run, all registered ones when `None`
+    selected: Option<Vec<String>>,
+    options: RenderOptions,
+}
+
+impl Fetcher {
+    pub fn new() -> Fetcher {
+        Fetcher {
+            registry: Registry::builtin(),
+            selected: None,
+            options: RenderOptions::default(),
+        }
+    }
+
+    /// Only run the collectors called `names` (`os`, `user`, `status`,
+    /// `cpu`, `gpu`, `memory`, `swap`, `disk`, `packages` or registered ones)
+    /// and the ones they depend on. Items whose collector did not run show
+    /// `Unknown`.
+    pub fn collectors<I, S>(mut self, names: I) -> Fetcher
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.selected = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a collector, replacing a built-in one of the same name. It runs
+    /// unless `collectors` leaves it out.
+    pub fn register<C: Collector>(mut self, collector: C) -> Fetcher {
+        self.registry.register(collector);
+        self
+    }
+
+    /// Run the selected collectors
+    pub fn fetch(&self) -> SystemInfo {
+        match &self.selected {
+            Some(names) => {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                self.registry.collect(&names)
+            }
+            None => self.registry.collect_all(),
+        }
+    }
+
+    /// Whether the output is written to stdout, in which case the terminal
+    /// there is asked about its color support, hyperlinks, graphics and size
+    pub fn terminal(mut self, terminal: bool) -> Fetcher {
+        self.options.terminal = terminal;
+        self
+    }
+
+    /// Draw with `depth` colors, whatever the config's `color` option says
+    pub fn color_depth(mut self, depth: ColorDepth) -> Fetcher {
+        self.options.color_depth = Some(depth);
+        self
+    }
+
+    /// Fit the output in `columns` columns
+    pub fn width(mut self, columns: usize) -> Fetcher {
+        self.options.width = Some(columns);
+        self
+    }
+
+    /// Draw the logo and `info` with `config` to `out`
+    pub fn render<W: Write>(&self, config: &Config, info: &SystemInfo, out: &mut W) -> Result<()> {
+        display::render_output(config, info, &self.options, out)?;
+        Ok(())
+    }
+
+    /// List the available themes with a sample of their colors to `out`
+    pub fn list_themes<W: Write>(&self, config: &Config, out: &mut W) -> Result<()> {
+        display::render_theme_list(config, &self.options, out)?;
+        Ok(())
+    }
+}
+
+impl Default for Fetcher {
+    fn default() -> Fetcher {
+        Fetcher::new()
+    }
+}
@@ -1,46 +1,65 @@
 //! swiftfetch library
 //!
-//! A fast and simple system information fetch tool written in Rust.
+//! A fast and simple system information fetch tool written in Rust. The
+//! `swiftfetch` binary is a thin wrapper around this crate, which other
+//! tools can use to gather the same information or draw the same output.
+//!
+//! ```no_run
+//! use swiftfetch::{parse_config, ColorDepth, Fetcher};
+//!
+//! // Only what a status bar needs
+//! let info = Fetcher::new().collectors(["memory", "status"]).fetch();
+//! if let (Some(memory), Some(status)) = (info.memory(), info.status()) {
+//!     println!("{} bytes used, up {}s", memory.used_bytes, status.uptime_seconds);
+//! }
+//!
+//! // The full fetch output, rendered into a buffer with 256 colors and
+//! // fitted to 80 columns
+//! let config = parse_config(include_str!("../config/config.toml"))?;
+//! let fetcher = Fetcher::new().color_depth(ColorDepth::Ansi256).width(80);
+//! let info = fetcher.fetch();
+//! let mut out = Vec::new();
+//! fetcher.render(&config, &info, &mut out)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Values can be added by implementing [`Collector`] and passing it to
+//! [`Fetcher::register`].
+//!
+//! Problems that are worked around, such as an unknown color name in the
+//! config, are not printed; collect them with [`take_warnings`].
 
-pub mod collectors;
+mod collectors;
 pub mod config;
 pub mod data;
-pub mod display;
+mod display;
 pub mod error;
-pub mod utils;
+mod fetcher;
+mod theme;
+mod utils;
+mod warnings;
 
-pub use data::{HardwareInfo, OsInfo, PackageInfo, SystemInfo, SystemStatus, UserInfo};
+pub use collectors::registry::{Collector, SystemInfo};
+pub use config::{ensure_user_config_exists, load_config, parse_config, Config};
+pub use data::{
+    BatteryInfo, CpuInfo, DiskInfo, Gpu, GpuInfo, GpuKind, MemoryInfo, OsInfo, PackageInfo,
+    SystemStatus, UserInfo, Value,
+};
+pub use display::ColorDepth;
 pub use error::{Result, SwiftfetchError};
+pub use fetcher::Fetcher;
+pub use warnings::take_warnings;
 
-/// Collect all system information (parallelized for speed)
+/// Collect all system information (parallelized for speed), failing if
+/// any collector did
+#[deprecated(
+    since = "0.2.0",
+    note = "use `Fetcher::new().fetch()`, which keeps what could be collected"
+)]
 pub fn collect_system_info() -> Result<SystemInfo> {
-    // Collect independent data in parallel using nested joins
-    // Rayon::join only takes 2 closures, so we nest them
-    let ((os, hardware), (packages, (status, user))) = rayon::join(
-        || {
-            rayon::join(
-                || collectors::system::collect_os_info(),
-                || collectors::hardware::collect_hardware_info(),
-            )
-        },
-        || {
-            rayon::join(
-                || collectors::packages::collect_package_info(),
-                || {
-                    rayon::join(
-                        || collectors::system::collect_system_status(),
-                        || collectors::system::collect_user_info(),
-                    )
-                },
-            )
-        },
-    );
-
-    Ok(SystemInfo {
-        os: os?,
-        hardware: hardware?,
-        packages: packages?,
-        status: status?,
-        user: user?,
-    })
+    let info = Fetcher::new().fetch();
+    if let Some((_, err)) = info.errors().next() {
+        return Err(err.clone());
+    }
+    Ok(info)
 }
@@ -1,78 +1,89 @@
-mod collectors;
-mod config;
-mod data;
-mod display;
-mod error;
-mod utils;
+use std::io::Write;
+use swiftfetch::{ensure_user_config_exists, load_config, take_warnings, Fetcher, Result};
 
-use crate::data::SystemInfo;
-use crate::error::Result;
-use config::{ensure_user_config_exists, load_config};
-use display::{render_output, SystemData};
+/// Command line options, which take precedence over the config file
+struct Args {
+    color: Option<String>,
+    list_themes: bool,
+}
 
-fn main() -> Result<()> {
-    ensure_user_config_exists();
-    let config = load_config();
+fn parse_args() -> std::result::Result<Args, String> {
+    let mut args = Args {
+        color: None,
+        list_themes: false,
+    };
+    let mut iter = std::env::args().skip(1);
 
-    let system_info = collect_system_info()?;
+    while let Some(arg) = iter.next() {
+        if arg == "--list-themes" {
+            args.list_themes = true;
+            continue;
+        }
 
-    // Convert structured data to display format for backward compatibility
-    let system_data = SystemData {
-        os_name: system_info.os.name,
-        kernel_version: system_info.os.kernel_version,
-        cpu_brand: system_info.hardware.cpu.brand,
-        gpu: system_info.hardware.gpu.primary,
-        all_gpus: system_info.hardware.gpu.all_gpus,
-        username: system_info.user.username,
-        hostname: system_info.user.hostname,
-        wm_de: system_info.status.desktop_environment,
-        memory: system_info.hardware.memory.formatted,
-        pkg_count: system_info.packages.system_packages,
-        flatpak_pkg_count: system_info.packages.flatpak_packages,
-        uptime_formatted: system_info.status.uptime_formatted,
-        os_age: system_info.os.age,
-        editor: system_info.status.editor,
-        shell: system_info.status.shell,
-        terminal: system_info.status.terminal,
-        user_info: system_info.user.formatted_user_info,
-        disk_usage: system_info.hardware.disk.usage,
-        init_system: system_info.status.init_system,
-        battery_info: system_info.status.battery_info,
-    };
+        let color = if let Some(value) = arg.strip_prefix("--color=") {
+            value.to_string()
+        } else if arg == "--color" {
+            iter.next()
+                .ok_or("--color requires a value (auto, always or never)")?
+        } else {
+            return Err(format!("Unknown argument '{}'", arg));
+        };
 
-    render_output(&config, &system_data);
-    Ok(())
+        if !matches!(color.as_str(), "auto" | "always" | "never") {
+            return Err(format!(
+                "Invalid --color value '{}' (expected auto, always or never)",
+                color
+            ));
+        }
+        args.color = Some(color);
+    }
+
+    Ok(args)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|err| {
+        eprintln!("swiftfetch: {}", err);
+        std::process::exit(2);
+    });
+
+    if let Err(err) = run(args) {
+        print_warnings();
+        eprintln!("swiftfetch: {}", err);
+        std::process::exit(1);
+    }
 }
 
-/// Collect all system information (parallelized for speed)
-fn collect_system_info() -> Result<SystemInfo> {
-    // Collect independent data in parallel using nested joins
-    // Rayon::join only takes 2 closures, so we nest them
-    let ((os, hardware), (packages, (status, user))) = rayon::join(
-        || {
-            rayon::join(
-                || collectors::system::collect_os_info(),
-                || collectors::hardware::collect_hardware_info(),
-            )
-        },
-        || {
-            rayon::join(
-                || collectors::packages::collect_package_info(),
-                || {
-                    rayon::join(
-                        || collectors::system::collect_system_status(),
-                        || collectors::system::collect_user_info(),
-                    )
-                },
-            )
-        },
-    );
+fn run(args: Args) -> Result<()> {
+    if let Some(path) = ensure_user_config_exists()? {
+        println!("Created config at {}", path.display());
+    }
+    let mut config = load_config()?;
+    if let Some(color) = args.color {
+        config.display.color = Some(color);
+    }
+    print_warnings();
+
+    // Rendered into a buffer so its warnings come before the output
+    let fetcher = Fetcher::new().terminal(true);
+    let mut out = Vec::new();
+    if args.list_themes {
+        fetcher.list_themes(&config, &mut out)?;
+    } else {
+        let info = fetcher.fetch();
+        for (name, err) in info.errors() {
+            eprintln!("swiftfetch: could not collect {}: {}", name, err);
+        }
+        fetcher.render(&config, &info, &mut out)?;
+    }
+    print_warnings();
+
+    std::io::stdout().lock().write_all(&out)?;
+    Ok(())
+}
 
-    Ok(SystemInfo {
-        os: os?,
-        hardware: hardware?,
-        packages: packages?,
-        status: status?,
-        user: user?,
-    })
+fn print_warnings() {
+    for warning in take_warnings() {
+        eprintln!("{}", warning);
+    }
 }
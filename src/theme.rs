@@ -0,0 +1,125 @@
+//! Named color palettes, bundled with swiftfetch or read from
+//! `~/.config/swiftfetch/themes/<name>.toml`
+
+use crate::config::ColorSpec;
+use crate::warnings::warn;
+use dirs::config_dir;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
+
+const BUNDLED: &[(&str, &str)] = &[
+    (
+        "catppuccin-latte",
+        include_str!("../themes/catppuccin-latte.toml"),
+    ),
+    (
+        "catppuccin-mocha",
+        include_str!("../themes/catppuccin-mocha.toml"),
+    ),
+    ("dracula", include_str!("../themes/dracula.toml")),
+    ("gruvbox-dark", include_str!("../themes/gruvbox-dark.toml")),
+    ("nord", include_str!("../themes/nord.toml")),
+    ("rose-pine", include_str!("../themes/rose-pine.toml")),
+    ("tokyo-night", include_str!("../themes/tokyo-night.toml")),
+];
+
+/// A theme file holds a `[colors]` table like the one in the config
+#[derive(Deserialize)]
+struct ThemeFile {
+    #[serde(default)]
+    colors: HashMap<String, ColorSpec>,
+}
+
+pub struct Theme {
+    pub name: String,
+    /// `bundled`, or the path of the user's theme file
+    pub source: String,
+    pub colors: HashMap<String, ColorSpec>,
+}
+
+fn user_theme_dir() -> Option<PathBuf> {
+    config_dir().map(|p| p.join("swiftfetch/themes"))
+}
+
+fn parse(data: &str, origin: &str) -> Option<HashMap<String, ColorSpec>> {
+    match toml::de::from_str::<ThemeFile>(data) {
+        Ok(theme) => Some(theme.colors),
+        Err(err) => {
+            warn(format!("Failed to parse theme {}: {}", origin, err));
+            None
+        }
+    }
+}
+
+/// Palette of the theme called `name`. A user theme file shadows a bundled
+/// theme of the same name.
+pub fn load(name: &str) -> Option<HashMap<String, ColorSpec>> {
+    // Names are file stems in the themes directory, never paths
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return None;
+    }
+
+    let user_path = user_theme_dir()
+        .map(|dir| dir.join(format!("{}.toml", name)))
+        .filter(|path| path.is_file());
+    if let Some(path) = user_path {
+        let data = fs::read_to_string(&path).ok()?;
+        return parse(&data, &path.display().to_string());
+    }
+
+    BUNDLED
+        .iter()
+        .find(|(bundled, _)| *bundled == name)
+        .and_then(|(bundled, data)| parse(data, bundled))
+}
+
+/// Every available theme, sorted by name
+pub fn all() -> Vec<Theme> {
+    let mut themes = BTreeMap::new();
+    for (name, data) in BUNDLED {
+        if let Some(colors) = parse(data, name) {
+            themes.insert(
+                name.to_string(),
+                Theme {
+                    name: name.to_string(),
+                    source: "bundled".to_string(),
+                    colors,
+                },
+            );
+        }
+    }
+
+    let entries = user_theme_dir().and_then(|dir| fs::read_dir(dir).ok());
+    for entry in entries.into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(name) = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        let source = path.display().to_string();
+        let colors = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| parse(&data, &source));
+        if let Some(colors) = colors {
+            themes.insert(
+                name.clone(),
+                Theme {
+                    name,
+                    source,
+                    colors,
+                },
+            );
+        }
+    }
+
+    themes.into_values().collect()
+}
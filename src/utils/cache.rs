@@ -0,0 +1,104 @@
+//! On-disk cache helpers shared by the artwork pipelines
+
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Stable 64-bit FNV-1a hasher.
+///
+/// `DefaultHasher` is not guaranteed to produce the same output across Rust
+/// releases, which would silently invalidate every cache entry on upgrade.
+pub struct CacheKey(u64);
+
+impl CacheKey {
+    pub fn new() -> Self {
+        CacheKey(0xcbf2_9ce4_8422_2325)
+    }
+
+    /// Mix a string component into the key (length-prefixed so "ab"+"c" != "a"+"bc")
+    pub fn push_str(&mut self, value: &str) -> &mut Self {
+        self.write_u64(value.len() as u64);
+        self.write(value.as_bytes());
+        self
+    }
+
+    /// Mix a numeric component into the key
+    pub fn push_u64(&mut self, value: u64) -> &mut Self {
+        self.write_u64(value);
+        self
+    }
+
+    /// Mix the source file identity (path, size and mtime) into the key
+    pub fn push_file(&mut self, path: &Path) -> std::io::Result<&mut Self> {
+        let metadata = fs::metadata(path)?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        self.push_str(&canonical.to_string_lossy());
+        self.push_u64(metadata.len());
+        self.push_u64(mtime.as_secs());
+        self.push_u64(mtime.subsec_nanos() as u64);
+        Ok(self)
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+impl Default for CacheKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for CacheKey {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Resolve `$XDG_CACHE_HOME/swiftfetch/<namespace>`, creating it if needed
+pub fn cache_dir(namespace: &str) -> Option<PathBuf> {
+    let dir = dirs::cache_dir()?.join("swiftfetch").join(namespace);
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+/// Path of a cache entry (which may or may not exist yet)
+pub fn entry_path(namespace: &str, file_name: &str) -> Option<PathBuf> {
+    Some(cache_dir(namespace)?.join(file_name))
+}
+
+/// Read a cache entry, returning `None` on any miss or error
+pub fn read_entry(namespace: &str, file_name: &str) -> Option<Vec<u8>> {
+    fs::read(entry_path(namespace, file_name)?).ok()
+}
+
+/// Write a cache entry atomically (write to a temp file, then rename) and
+/// return its path.
+///
+/// Failures only yield `None`: the cache is purely an optimization.
+pub fn write_entry(namespace: &str, file_name: &str, contents: &[u8]) -> Option<PathBuf> {
+    let dir = cache_dir(namespace)?;
+    let final_path = dir.join(file_name);
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    fs::write(&temp_path, contents).ok()?;
+    if fs::rename(&temp_path, &final_path).is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return None;
+    }
+    Some(final_path)
+}
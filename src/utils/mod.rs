@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod command;
 pub mod file;
 pub mod parsing;
+pub mod terminal;
@@ -20,18 +20,6 @@ pub fn kb_to_gb(kb_str: &str) -> Result<f64> {
     Ok(kb as f64 / 1_048_576.0) // 1024^2
 }
 
-/// Format uptime from seconds
-pub fn format_uptime(seconds: u64) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-
-    if hours > 0 {
-        format!("{}h {:02}m", hours, minutes)
-    } else {
-        format!("{}m", minutes)
-    }
-}
-
 /// Clean and simplify GPU names
 #[allow(dead_code)]
 pub fn clean_gpu_name(raw_name: &str) -> String {
@@ -0,0 +1,278 @@
+//! Controlling terminal helpers (tty detection and escape-sequence queries)
+
+use std::ffi::CString;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+const CELL_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Check whether stdout is attached to a terminal
+pub fn stdout_is_tty() -> bool {
+    unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+/// Size of one character cell in pixels, or `None` when it cannot be
+/// determined.
+///
+/// The pixel fields of `TIOCGWINSZ` are used when set. Many terminals leave
+/// them zero, so the terminal is then asked directly: `CSI 16 t` reports the
+/// cell size, and `CSI 14 t` (text area in pixels) divided by the grid from
+/// `CSI 18 t` covers terminals that only answer the latter. The result is
+/// computed once per process.
+pub fn cell_size() -> Option<(f32, f32)> {
+    static CELL_SIZE: OnceLock<Option<(f32, f32)>> = OnceLock::new();
+    *CELL_SIZE.get_or_init(|| {
+        let window = window_size();
+        if let Some(ws) = window.filter(|ws| ws.ws_xpixel > 0 && ws.ws_ypixel > 0) {
+            return Some((
+                ws.ws_xpixel as f32 / ws.ws_col as f32,
+                ws.ws_ypixel as f32 / ws.ws_row as f32,
+            ));
+        }
+        query_cell_size(window.map(|ws| (ws.ws_col, ws.ws_row)))
+    })
+}
+
+/// Width of the terminal in columns, from `TIOCGWINSZ` or else `$COLUMNS`.
+///
+/// `None` when stdout is not a terminal and `COLUMNS` is unset, in which case
+/// output should not be fitted to any width.
+pub fn columns() -> Option<usize> {
+    window_size()
+        .map(|ws| ws.ws_col as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .filter(|columns| *columns > 0)
+}
+
+/// `TIOCGWINSZ` on stdout, if it is a terminal with a non-empty grid
+fn window_size() -> Option<libc::winsize> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) };
+    (result == 0 && ws.ws_col > 0 && ws.ws_row > 0).then_some(ws)
+}
+
+fn query_cell_size(grid: Option<(u16, u16)>) -> Option<(f32, f32)> {
+    if !stdout_is_tty() {
+        return None;
+    }
+    let mut tty = Tty::open()?;
+    // DA1 is answered by every terminal and marks the end of the replies
+    let response = tty.query(
+        b"\x1b[16t\x1b[14t\x1b[18t\x1b[c",
+        |bytes| find_da1(&String::from_utf8_lossy(bytes)).is_some(),
+        CELL_QUERY_TIMEOUT,
+    );
+    drop(tty);
+    cell_size_from_replies(&String::from_utf8_lossy(&response), grid)
+}
+
+/// Cell size from the replies to `CSI 16 t`, `CSI 14 t` and `CSI 18 t`, with
+/// `grid` (columns, rows) standing in for a missing `CSI 18 t` reply
+fn cell_size_from_replies(response: &str, grid: Option<(u16, u16)>) -> Option<(f32, f32)> {
+    if let Some((height, width)) = window_op_reply(response, 6) {
+        return Some((width as f32, height as f32));
+    }
+    let (height, width) = window_op_reply(response, 4)?;
+    let (columns, rows) = window_op_reply(response, 8)
+        .map(|(rows, columns)| (columns, rows))
+        .or(grid.map(|(columns, rows)| (columns as u32, rows as u32)))?;
+    Some((width as f32 / columns as f32, height as f32 / rows as f32))
+}
+
+/// Parse a `CSI kind ; a ; b t` window manipulation reply into `(a, b)`,
+/// ignoring zero values some terminals send for unknown sizes.
+fn window_op_reply(response: &str, kind: u32) -> Option<(u32, u32)> {
+    let prefix = format!("\x1b[{};", kind);
+    let start = response.find(&prefix)? + prefix.len();
+    let body = &response[start..];
+    let end = body.find('t')?;
+    let (a, b) = body[..end].split_once(';')?;
+    let (a, b) = (a.parse().ok()?, b.parse().ok()?);
+    (a > 0 && b > 0).then_some((a, b))
+}
+
+/// Locate the parameters of a `CSI ? Ps ; ... c` primary device attributes
+/// reply, skipping other `CSI ?` replies that arrive before it
+pub fn find_da1(response: &str) -> Option<&str> {
+    response.match_indices("\x1b[?").find_map(|(start, _)| {
+        let body = &response[start + 3..];
+        let end = body.find(|ch: char| !(ch.is_ascii_digit() || ch == ';'))?;
+        (body.as_bytes()[end] == b'c').then(|| &body[..end])
+    })
+}
+
+/// The controlling terminal, opened in raw mode for the lifetime of the value.
+///
+/// Queries are written to and answered through `/dev/tty` so they work even
+/// when stdin is redirected. The original terminal mode is restored on drop.
+pub struct Tty {
+    fd: libc::c_int,
+    original: libc::termios,
+}
+
+impl Tty {
+    pub fn open() -> Option<Tty> {
+        let path = CString::new("/dev/tty").ok()?;
+        unsafe {
+            let fd = libc::open(
+                path.as_ptr(),
+                libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC,
+            );
+            if fd < 0 {
+                return None;
+            }
+
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut original) != 0 {
+                libc::close(fd);
+                return None;
+            }
+
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            raw.c_cc[libc::VMIN] = 0;
+            raw.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+                libc::close(fd);
+                return None;
+            }
+
+            Some(Tty { fd, original })
+        }
+    }
+
+    /// Send `request` and collect the reply until `is_complete` accepts the
+    /// bytes read so far or `timeout` expires. Returns whatever was read.
+    pub fn query(
+        &mut self,
+        request: &[u8],
+        is_complete: impl Fn(&[u8]) -> bool,
+        timeout: Duration,
+    ) -> Vec<u8> {
+        let mut response = Vec::new();
+        if !self.write_all(request) {
+            return response;
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut buffer = [0u8; 256];
+        while !is_complete(&response) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            let mut poll_fd = libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let ready = unsafe { libc::poll(&mut poll_fd, 1, remaining.as_millis().max(1) as i32) };
+            if ready <= 0 {
+                break;
+            }
+
+            let read = unsafe {
+                libc::read(
+                    self.fd,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if read <= 0 {
+                break;
+            }
+            response.extend_from_slice(&buffer[..read as usize]);
+        }
+
+        response
+    }
+
+    fn write_all(&mut self, mut bytes: &[u8]) -> bool {
+        while !bytes.is_empty() {
+            let written =
+                unsafe { libc::write(self.fd, bytes.as_ptr() as *const libc::c_void, bytes.len()) };
+            if written <= 0 {
+                return false;
+            }
+            bytes = &bytes[written as usize..];
+        }
+        true
+    }
+}
+
+impl Drop for Tty {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn da1_reply() {
+        assert_eq!(find_da1("\x1b[?62;4;22c"), Some("62;4;22"));
+        assert_eq!(find_da1("\x1b[?6c"), Some("6"));
+    }
+
+    #[test]
+    fn da1_after_other_replies() {
+        // kitty graphics and XTVERSION replies come first
+        assert_eq!(
+            find_da1("\x1b_Gi=31;OK\x1b\\\x1bP>|kitty(0.35.2)\x1b\\\x1b[?62;c"),
+            Some("62;")
+        );
+        // A DECRPM reply is a `CSI ?` reply too, but not DA1
+        assert_eq!(find_da1("\x1b[?2026;2$y\x1b[?1;2c"), Some("1;2"));
+        assert_eq!(find_da1("noise\x1b[?64;4c"), Some("64;4"));
+    }
+
+    #[test]
+    fn window_op_replies() {
+        assert_eq!(window_op_reply("\x1b[6;20;10t", 6), Some((20, 10)));
+        assert_eq!(
+            window_op_reply("\x1b[6;20;10t\x1b[4;480;800t\x1b[8;24;80t", 8),
+            Some((24, 80))
+        );
+        // Terminals send zeros for sizes they do not know
+        assert_eq!(window_op_reply("\x1b[4;0;0t", 4), None);
+        // Cut off before the final byte
+        assert_eq!(window_op_reply("\x1b[4;480;80", 4), None);
+        assert_eq!(
+            window_op_reply("garbage\x1b[4;480;800t", 4),
+            Some((480, 800))
+        );
+    }
+
+    #[test]
+    fn cell_size_prefers_the_cell_reply() {
+        let response = "\x1b[6;20;10t\x1b[4;480;800t\x1b[8;24;80t\x1b[?62c";
+        assert_eq!(cell_size_from_replies(response, None), Some((10.0, 20.0)));
+    }
+
+    #[test]
+    fn cell_size_from_text_area_and_grid() {
+        let response = "\x1b[4;480;800t\x1b[8;24;80t\x1b[?62c";
+        assert_eq!(cell_size_from_replies(response, None), Some((10.0, 20.0)));
+        // Without a `CSI 18 t` reply the grid from the window size is used
+        let response = "\x1b[4;480;800t\x1b[?62c";
+        assert_eq!(
+            cell_size_from_replies(response, Some((100, 40))),
+            Some((8.0, 12.0))
+        );
+        assert_eq!(cell_size_from_replies(response, None), None);
+        assert_eq!(cell_size_from_replies("\x1b[?62c", Some((80, 24))), None);
+    }
+
+    #[test]
+    fn da1_split_or_missing() {
+        assert_eq!(find_da1("\x1b[?62;4"), None);
+        assert_eq!(find_da1("\x1b[62;4c"), None);
+        assert_eq!(find_da1(""), None);
+    }
+}
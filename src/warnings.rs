@@ -0,0 +1,19 @@
+//! Problems swiftfetch works around, such as an unknown color name in the
+//! config. They are kept for the caller to show instead of being printed.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Note a problem that was worked around
+pub(crate) fn warn(message: impl Into<String>) {
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(message.into()));
+}
+
+/// Warnings noted on this thread since the last call, oldest first: from
+/// loading a config, rendering, and listing themes
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS.with(|warnings| warnings.take())
+}